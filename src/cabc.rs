@@ -0,0 +1,127 @@
+//! Backlight brightness and CABC (Content Adaptive Brightness Control)
+
+use crate::otm8009a::{read_byte, write_short};
+use crate::{regs, Error, Otm8009A};
+use embedded_display_controller::dsi::DsiHostCtrlIo;
+
+/// WRCTRLD / RDCTRLD bits
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DisplayControl {
+    /// Brightness control block (BCTRL)
+    pub brightness_control: bool,
+    /// Display dimming (DD)
+    pub dimming: bool,
+    /// Backlight (BL)
+    pub backlight: bool,
+}
+
+impl DisplayControl {
+    pub const fn bits(&self) -> u8 {
+        let mut bits = 0;
+        if self.brightness_control {
+            bits |= regs::OTM8009A_CTRLD_BCTRL;
+        }
+        if self.dimming {
+            bits |= regs::OTM8009A_CTRLD_DD;
+        }
+        if self.backlight {
+            bits |= regs::OTM8009A_CTRLD_BL;
+        }
+        bits
+    }
+
+    pub const fn from_bits(bits: u8) -> Self {
+        DisplayControl {
+            brightness_control: bits & regs::OTM8009A_CTRLD_BCTRL != 0,
+            dimming: bits & regs::OTM8009A_CTRLD_DD != 0,
+            backlight: bits & regs::OTM8009A_CTRLD_BL != 0,
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CabcMode {
+    Off = 0b00,
+    UserInterface = 0b01,
+    StillPicture = 0b10,
+    MovingImage = 0b11,
+}
+
+impl CabcMode {
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => CabcMode::Off,
+            0b01 => CabcMode::UserInterface,
+            0b10 => CabcMode::StillPicture,
+            _ => CabcMode::MovingImage,
+        }
+    }
+}
+
+impl Otm8009A {
+    /// Sets the display brightness (WRDISBV), 0 is the lowest
+    pub fn set_brightness<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        brightness: u8,
+    ) -> Result<(), Error<D::Error>> {
+        write_short(dsi, regs::OTM8009A_CMD_WRDISBV, brightness)
+    }
+
+    /// Reads the display brightness back (RDDISBV)
+    pub fn brightness<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<u8, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDDISBV)
+    }
+
+    /// Enables or disables the brightness block, dimming and backlight (WRCTRLD)
+    pub fn set_display_control<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        control: DisplayControl,
+    ) -> Result<(), Error<D::Error>> {
+        write_short(dsi, regs::OTM8009A_CMD_WRCTRLD, control.bits())
+    }
+
+    /// Reads the display control bits back (RDCTRLD)
+    pub fn display_control<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<DisplayControl, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDCTRLD).map(DisplayControl::from_bits)
+    }
+
+    /// Selects the CABC mode (WRCABC)
+    pub fn set_cabc_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        mode: CabcMode,
+    ) -> Result<(), Error<D::Error>> {
+        write_short(dsi, regs::OTM8009A_CMD_WRCABC, mode as u8)
+    }
+
+    /// Reads the CABC mode back (RDCABC)
+    pub fn cabc_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<CabcMode, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDCABC).map(CabcMode::from_bits)
+    }
+
+    /// Sets the minimum brightness CABC may dim down to (WRCABCMB)
+    pub fn set_cabc_min_brightness<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        brightness: u8,
+    ) -> Result<(), Error<D::Error>> {
+        write_short(dsi, regs::OTM8009A_CMD_WRCABCMB, brightness)
+    }
+
+    /// Reads the CABC minimum brightness back (RDCABCMB)
+    pub fn cabc_min_brightness<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<u8, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDCABCMB)
+    }
+}
@@ -0,0 +1,222 @@
+//! Analog gamma correction tables (GMCT2.2P 0xE100 and GMCT2.2N 0xE200)
+//!
+//! Each table holds 16 six bit entries, one per gray level tap from V0 to V255,
+//! for the positive and negative source polarity. The reference 2.2 table
+//! is the one written by the original bring-up sequence.
+//!
+//! Presets for 1.8, 2.5 and linear are not provided yet: the encoding of the
+//! table entries is not documented in any source the driver can cite, and
+//! tables derived by guesswork could drive the source outputs out of range.
+//! Until the vendor tables are available, such curves have to be given as raw
+//! register values, for example taken from a panel vendor tool.
+
+use crate::otm8009a::{read_dcs, write_short};
+use crate::{regs, Error, Otm8009A};
+use embedded_display_controller::dsi::{DsiHostCtrlIo, DsiWriteCommand};
+
+/// Positive and negative polarity gamma table registers, at address shift
+/// 0x00
+///
+/// Unlike the other vendor registers the tables are accessed with CMD2 locked,
+/// like the reference bring-up sequence does.
+const GAMMA_TABLES: [u8; 2] = [0xe1, 0xe2];
+/// Digital gamma lookup tables for red, green and blue
+const DIGITAL_GAMMA: [u16; 3] = [0xec00, 0xed00, 0xee00];
+
+/// Control points per digital gamma channel, gray levels 0, 8, .., 248 and 255
+pub const DIGITAL_GAMMA_POINTS: usize = 33;
+
+/// Largest value of a gamma table entry
+pub const GAMMA_ENTRY_MAX: u8 = 0x3f;
+
+/// Why a gamma table could not be built
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GammaError {
+    /// A channel gain is not within `0.0..=1.0`
+    Gain,
+}
+
+/// Positive and negative polarity gamma tables
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Gamma {
+    pub positive: [u8; 16],
+    pub negative: [u8; 16],
+}
+
+impl Gamma {
+    /// Reference gamma 2.2 curve
+    pub const GAMMA_2_2: Gamma = Gamma::symmetric([
+        0x00, 0x09, 0x0F, 0x0E, 0x07, 0x10, 0x0B, 0x0A, 0x04, 0x07, 0x0B, 0x08, 0x0F, 0x10, 0x0A,
+        0x01,
+    ]);
+
+    /// Same table for both polarities
+    pub const fn symmetric(table: [u8; 16]) -> Self {
+        Gamma {
+            positive: table,
+            negative: table,
+        }
+    }
+
+    /// Checks that every entry fits the six bit registers
+    pub fn is_valid(&self) -> bool {
+        self.positive
+            .iter()
+            .chain(&self.negative)
+            .all(|&entry| entry <= GAMMA_ENTRY_MAX)
+    }
+}
+
+impl Default for Gamma {
+    fn default() -> Self {
+        Gamma::GAMMA_2_2
+    }
+}
+
+/// Per-channel digital gamma lookup tables
+///
+/// Each table maps the gray levels 0, 8, .., 248 and 255 of one colour channel
+/// to an output level, on top of the shared analog gamma. Lowering the output
+/// of one channel against the others shifts the white point.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DigitalGamma {
+    pub red: [u8; DIGITAL_GAMMA_POINTS],
+    pub green: [u8; DIGITAL_GAMMA_POINTS],
+    pub blue: [u8; DIGITAL_GAMMA_POINTS],
+}
+
+impl DigitalGamma {
+    /// Pass-through tables
+    pub const IDENTITY: DigitalGamma = DigitalGamma {
+        red: identity(),
+        green: identity(),
+        blue: identity(),
+    };
+
+    /// Tables scaling each channel by its gain, for white-point correction
+    ///
+    /// Gains are within `0.0..=1.0`, the channel that needs no correction
+    /// should get `1.0` so the panel keeps its full brightness.
+    pub fn from_gains(red: f32, green: f32, blue: f32) -> Result<Self, GammaError> {
+        Ok(DigitalGamma {
+            red: scaled(red)?,
+            green: scaled(green)?,
+            blue: scaled(blue)?,
+        })
+    }
+
+    fn tables(&self) -> [&[u8; DIGITAL_GAMMA_POINTS]; 3] {
+        [&self.red, &self.green, &self.blue]
+    }
+}
+
+impl Default for DigitalGamma {
+    fn default() -> Self {
+        DigitalGamma::IDENTITY
+    }
+}
+
+const fn identity() -> [u8; DIGITAL_GAMMA_POINTS] {
+    let mut table = [0xff; DIGITAL_GAMMA_POINTS];
+    let mut i = 0;
+    while i < DIGITAL_GAMMA_POINTS - 1 {
+        table[i] = (i * 8) as u8;
+        i += 1;
+    }
+    table
+}
+
+fn scaled(gain: f32) -> Result<[u8; DIGITAL_GAMMA_POINTS], GammaError> {
+    if !(0.0..=1.0).contains(&gain) {
+        return Err(GammaError::Gain);
+    }
+    let mut table = identity();
+    for entry in &mut table {
+        *entry = libm::roundf(f32::from(*entry) * gain) as u8;
+    }
+    Ok(table)
+}
+
+impl Otm8009A {
+    /// Loads new gamma tables
+    pub fn set_gamma<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        gamma: Gamma,
+    ) -> Result<(), Error<D::Error>> {
+        let mut config = self.config.ok_or(Error::PowerState)?;
+        config.gamma = gamma;
+        config.validate().map_err(Error::InvalidConfig)?;
+        write_gamma(dsi, &gamma)?;
+        self.config = Some(config);
+        Ok(())
+    }
+
+    /// Loads per-channel digital gamma tables
+    pub fn set_digital_gamma<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        digital_gamma: DigitalGamma,
+    ) -> Result<(), Error<D::Error>> {
+        let mut config = self.config.ok_or(Error::PowerState)?;
+        self.write_digital_gamma(dsi, &digital_gamma)?;
+        config.digital_gamma = Some(digital_gamma);
+        self.config = Some(config);
+        Ok(())
+    }
+
+    /// Reads the currently loaded digital gamma tables back
+    pub fn digital_gamma<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<DigitalGamma, Error<D::Error>> {
+        let mut digital_gamma = DigitalGamma::IDENTITY;
+        self.with_cmd2(dsi, |this, dsi| {
+            let DigitalGamma { red, green, blue } = &mut digital_gamma;
+            for (addr, table) in DIGITAL_GAMMA.into_iter().zip([red, green, blue]) {
+                this.read_vendor_reg(dsi, addr, table)?;
+            }
+            Ok(())
+        })?;
+        Ok(digital_gamma)
+    }
+
+    pub(crate) fn write_digital_gamma<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        digital_gamma: &DigitalGamma,
+    ) -> Result<(), Error<D::Error>> {
+        self.with_cmd2(dsi, |this, dsi| {
+            for (addr, table) in DIGITAL_GAMMA.into_iter().zip(digital_gamma.tables()) {
+                this.write_vendor_reg(dsi, addr, table)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Reads the currently loaded gamma tables back
+    pub fn gamma<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<Gamma, Error<D::Error>> {
+        let mut gamma = Gamma::symmetric([0; 16]);
+        let Gamma { positive, negative } = &mut gamma;
+        for (arg, table) in GAMMA_TABLES.into_iter().zip([positive, negative]) {
+            write_short(dsi, regs::OTM8009A_CMD_NOP, 0x00)?;
+            read_dcs(dsi, arg, table)?;
+        }
+        Ok(gamma)
+    }
+}
+
+/// Writes both gamma tables, each after an address shift to 0x00
+pub(crate) fn write_gamma<D: DsiHostCtrlIo>(
+    dsi: &mut D,
+    gamma: &Gamma,
+) -> Result<(), Error<D::Error>> {
+    for (arg, table) in GAMMA_TABLES
+        .into_iter()
+        .zip([&gamma.positive, &gamma.negative])
+    {
+        write_short(dsi, regs::OTM8009A_CMD_NOP, 0x00)?;
+        dsi.write(DsiWriteCommand::DcsLongWrite { arg, data: table })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! GRAM access for DSI adapted command mode
+
+use crate::otm8009a::write_window;
+use crate::{regs, Error, Otm8009A, PixelFormat, PowerState};
+use embedded_display_controller::dsi::{DsiHostCtrlIo, DsiReadCommand, DsiWriteCommand};
+
+/// Pixels per RGB565 readback packet, bounded by the conversion buffer
+const RGB565_READ_PIXELS: usize = 85;
+
+/// Rectangular GRAM area, both ends inclusive
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Window {
+    pub x0: u16,
+    pub y0: u16,
+    pub x1: u16,
+    pub y1: u16,
+}
+
+impl Window {
+    pub const fn new(x0: u16, y0: u16, x1: u16, y1: u16) -> Self {
+        Window { x0, y0, x1, y1 }
+    }
+
+    /// Number of pixels covered, zero if the corners are swapped
+    pub const fn pixel_count(&self) -> usize {
+        if self.x0 > self.x1 || self.y0 > self.y1 {
+            return 0;
+        }
+        (self.x1 - self.x0 + 1) as usize * (self.y1 - self.y0 + 1) as usize
+    }
+}
+
+impl Otm8009A {
+    /// Sets the GRAM window for the following pixel writes, both ends inclusive
+    pub fn set_window<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+    ) -> Result<(), Error<D::Error>> {
+        self.check_window(Window::new(x0, y0, x1, y1))?;
+        write_window(dsi, x0, y0, x1, y1)
+    }
+
+    /// Streams `pixels` into the current window, starting at its first pixel
+    ///
+    /// `pixels` holds whole pixels in the configured pixel format. They are
+    /// sent as one RAMWR followed by as many RAMWRC long writes as needed, each
+    /// packet (DCS command included) at most `max_packet_size` bytes long and
+    /// carrying whole pixels only.
+    pub fn write_pixels<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        pixels: &[u8],
+        max_packet_size: u16,
+    ) -> Result<(), Error<D::Error>> {
+        let chunk_len = self.pixel_chunk_len(max_packet_size)?;
+        if !pixels.len().is_multiple_of(self.bytes_per_pixel()) {
+            return Err(Error::WriteLength);
+        }
+        let mut arg = regs::OTM8009A_CMD_RAMWR;
+        for chunk in pixels.chunks(chunk_len) {
+            dsi.write(DsiWriteCommand::DcsLongWrite { arg, data: chunk })?;
+            arg = regs::OTM8009A_CMD_RAMWRC;
+        }
+        Ok(())
+    }
+
+    /// Reads `window` back from GRAM into `buf`
+    ///
+    /// The panel returns 24 bits per pixel whatever the interface pixel format
+    /// is, `buf` receives them converted to the configured pixel format, in the
+    /// same byte order [`write_pixels`](Self::write_pixels) takes. It must hold
+    /// exactly the pixels of `window`. The data is read with one RAMRD followed
+    /// by as many RAMRDC as needed, each returning whole pixels and at most
+    /// `max_packet_size` bytes, the largest read the host can receive.
+    pub fn read_pixels<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        window: Window,
+        buf: &mut [u8],
+        max_packet_size: u16,
+    ) -> Result<(), Error<D::Error>> {
+        self.check_gram_access()?;
+        let pixel_format = self.config.ok_or(Error::PowerState)?.pixel_format;
+        let bytes_per_pixel = pixel_format.bytes_per_pixel();
+        if buf.len() != window.pixel_count() * bytes_per_pixel {
+            return Err(Error::ReadLength);
+        }
+        let mut pixels_per_read = usize::from(max_packet_size) / 3;
+        if pixel_format == PixelFormat::Rgb565 {
+            pixels_per_read = pixels_per_read.min(RGB565_READ_PIXELS);
+        }
+        if pixels_per_read == 0 {
+            return Err(Error::ReadLength);
+        }
+        self.set_window(dsi, window.x0, window.y0, window.x1, window.y1)?;
+
+        let mut scratch = [0u8; RGB565_READ_PIXELS * 3];
+        let mut arg = regs::OTM8009A_CMD_RAMRD;
+        let mut return_size = 0;
+        for chunk in buf.chunks_mut(pixels_per_read * bytes_per_pixel) {
+            let pixels = chunk.len() / bytes_per_pixel;
+            let read_len = pixels * 3;
+            if read_len != return_size {
+                // Cannot truncate, read_len <= max_packet_size
+                return_size = read_len;
+                dsi.write(DsiWriteCommand::SetMaximumReturnPacketSize(read_len as u16))?;
+            }
+            if pixel_format == PixelFormat::Rgb565 {
+                let raw = &mut scratch[..read_len];
+                dsi.read(DsiReadCommand::DcsShort { arg }, raw)?;
+                for (rgb, out) in raw.chunks_exact(3).zip(chunk.chunks_exact_mut(2)) {
+                    let value = (u16::from(rgb[0] >> 3) << 11)
+                        | (u16::from(rgb[1] >> 2) << 5)
+                        | u16::from(rgb[2] >> 3);
+                    out.copy_from_slice(&value.to_be_bytes());
+                }
+            } else {
+                dsi.read(DsiReadCommand::DcsShort { arg }, chunk)?;
+            }
+            arg = regs::OTM8009A_CMD_RAMRDC;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn bytes_per_pixel(&self) -> usize {
+        self.config
+            .map_or(3, |config| config.pixel_format.bytes_per_pixel())
+    }
+
+    /// Largest whole-pixel payload fitting a packet of `max_packet_size` bytes
+    /// next to the DCS command, checking that pixel data may be transferred
+    pub(crate) fn pixel_chunk_len<E>(&self, max_packet_size: u16) -> Result<usize, Error<E>> {
+        self.check_gram_access()?;
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let chunk_len =
+            usize::from(max_packet_size).saturating_sub(1) / bytes_per_pixel * bytes_per_pixel;
+        if chunk_len == 0 {
+            return Err(Error::WriteLength);
+        }
+        Ok(chunk_len)
+    }
+
+    /// Checks that `window` is ordered and lies within the configured area
+    pub(crate) fn check_window<E>(&self, window: Window) -> Result<(), Error<E>> {
+        let config = self.config.ok_or(Error::PowerState)?;
+        let Window { x0, y0, x1, y1 } = window;
+        if x0 > x1 || y0 > y1 || x1 >= config.cols || y1 >= config.rows {
+            return Err(Error::OutOfBounds);
+        }
+        Ok(())
+    }
+
+    /// GRAM can only be accessed while the panel is initialised and awake
+    pub(crate) fn check_gram_access<E>(&self) -> Result<(), Error<E>> {
+        if !matches!(
+            self.power_state(),
+            PowerState::Awake | PowerState::DisplayOn
+        ) {
+            return Err(Error::PowerState);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+//! `embedded-graphics` draw target for DSI adapted command mode
+//!
+//! [`Otm8009ADisplay`] owns the DSI host and an initialised [`Otm8009A`] and
+//! draws straight into the panel GRAM, without an LTDC framebuffer. Filled
+//! rectangles become one CASET/PASET window followed by a RAMWR/RAMWRC stream,
+//! single pixels get a 1x1 window each.
+
+use crate::{regs, Error, Otm8009A, PixelFormat};
+use core::marker::PhantomData;
+use embedded_display_controller::dsi::{DsiHostCtrlIo, DsiWriteCommand};
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{Dimensions, OriginDimensions, Size};
+use embedded_graphics_core::pixelcolor::{IntoStorage, PixelColor, Rgb565, Rgb888};
+use embedded_graphics_core::primitives::{PointsIter, Rectangle};
+use embedded_graphics_core::Pixel;
+
+/// Largest pixel payload assembled on the stack for one packet
+const CHUNK_BUF_LEN: usize = 252;
+
+/// Colour types that map to a GRAM pixel format
+pub trait GramColor: PixelColor {
+    const PIXEL_FORMAT: PixelFormat;
+
+    /// Writes the pixel bytes in GRAM order to the start of `buf`
+    fn write_bytes(self, buf: &mut [u8]);
+}
+
+impl GramColor for Rgb565 {
+    const PIXEL_FORMAT: PixelFormat = PixelFormat::Rgb565;
+
+    fn write_bytes(self, buf: &mut [u8]) {
+        buf[..2].copy_from_slice(&self.into_storage().to_be_bytes());
+    }
+}
+
+impl GramColor for Rgb888 {
+    const PIXEL_FORMAT: PixelFormat = PixelFormat::Rgb888;
+
+    fn write_bytes(self, buf: &mut [u8]) {
+        buf[..3].copy_from_slice(&self.into_storage().to_be_bytes()[1..]);
+    }
+}
+
+/// [`DrawTarget`] drawing into the panel GRAM with colour type `C`
+pub struct Otm8009ADisplay<D, C> {
+    dsi: D,
+    panel: Otm8009A,
+    max_packet_size: u16,
+    _color: PhantomData<C>,
+}
+
+impl<D: DsiHostCtrlIo, C: GramColor> Otm8009ADisplay<D, C> {
+    /// Wraps an initialised panel, switching it to the pixel format of `C`
+    ///
+    /// `max_packet_size` is the largest DCS long write the host accepts,
+    /// command byte included.
+    pub fn new(
+        mut dsi: D,
+        mut panel: Otm8009A,
+        max_packet_size: u16,
+    ) -> Result<Self, Error<D::Error>> {
+        panel.set_pixel_format(&mut dsi, C::PIXEL_FORMAT)?;
+        Ok(Otm8009ADisplay {
+            dsi,
+            panel,
+            max_packet_size,
+            _color: PhantomData,
+        })
+    }
+
+    /// Gives back the DSI host and the driver
+    pub fn release(self) -> (D, Otm8009A) {
+        (self.dsi, self.panel)
+    }
+
+    pub fn dsi(&mut self) -> &mut D {
+        &mut self.dsi
+    }
+
+    pub fn panel(&self) -> &Otm8009A {
+        &self.panel
+    }
+
+    /// Sets the window to `area` and streams `colors` into it
+    ///
+    /// `area` must lie within the display.
+    fn stream(
+        &mut self,
+        area: &Rectangle,
+        colors: impl IntoIterator<Item = C>,
+    ) -> Result<(), Error<D::Error>> {
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        self.panel.set_window(
+            &mut self.dsi,
+            area.top_left.x as u16,
+            area.top_left.y as u16,
+            bottom_right.x as u16,
+            bottom_right.y as u16,
+        )?;
+
+        let bytes_per_pixel = C::PIXEL_FORMAT.bytes_per_pixel();
+        let chunk_len = self
+            .panel
+            .pixel_chunk_len(self.max_packet_size)?
+            .min(CHUNK_BUF_LEN / bytes_per_pixel * bytes_per_pixel);
+        let mut buf = [0u8; CHUNK_BUF_LEN];
+        let mut len = 0;
+        let mut arg = regs::OTM8009A_CMD_RAMWR;
+        for color in colors
+            .into_iter()
+            .take(area.size.width as usize * area.size.height as usize)
+        {
+            color.write_bytes(&mut buf[len..]);
+            len += bytes_per_pixel;
+            if len == chunk_len {
+                self.dsi.write(DsiWriteCommand::DcsLongWrite {
+                    arg,
+                    data: &buf[..len],
+                })?;
+                arg = regs::OTM8009A_CMD_RAMWRC;
+                len = 0;
+            }
+        }
+        if len > 0 {
+            self.dsi.write(DsiWriteCommand::DcsLongWrite {
+                arg,
+                data: &buf[..len],
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl<D, C> OriginDimensions for Otm8009ADisplay<D, C> {
+    fn size(&self) -> Size {
+        self.panel.config().map_or(Size::zero(), |config| {
+            Size::new(config.cols.into(), config.rows.into())
+        })
+    }
+}
+
+impl<D: DsiHostCtrlIo, C: GramColor> DrawTarget for Otm8009ADisplay<D, C> {
+    type Color = C;
+    type Error = Error<D::Error>;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounding_box = self.bounding_box();
+        for Pixel(point, color) in pixels {
+            if bounding_box.contains(point) {
+                self.stream(&Rectangle::new(point, Size::new(1, 1)), [color])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let bounding_box = self.bounding_box();
+        if bounding_box.intersection(area) == *area {
+            self.stream(area, colors)
+        } else {
+            self.draw_iter(
+                area.points()
+                    .zip(colors)
+                    .filter(|(point, _)| bounding_box.contains(*point))
+                    .map(|(point, color)| Pixel(point, color)),
+            )
+        }
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = self.bounding_box().intersection(area);
+        self.stream(&area, core::iter::repeat(color))
+    }
+}
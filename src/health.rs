@@ -0,0 +1,115 @@
+//! Panel hang detection and recovery
+//!
+//! After an ESD event the panel can latch up: it drops out of sleep-out or
+//! display-on, or loses its register contents, and goes black while the host
+//! keeps running. [`Otm8009A::health_check`] reads the state back and compares
+//! it with what the driver configured, [`Otm8009A::recover`] re-initialises the
+//! panel when that check fails. A panel that stops answering reads is treated
+//! as latched up as well.
+
+use crate::otm8009a::{read_byte, write_short};
+use crate::{regs, Error, Otm8009A, Otm8009AConfig, PowerMode, SelfDiagnostic};
+use embedded_display_controller::dsi::DsiHostCtrlIo;
+use embedded_hal::blocking::delay::DelayMs;
+
+/// Time to wait after SWRESET before SLPOUT may be sent
+const SWRESET_DELAY_MS: u32 = 120;
+
+/// Check of [`Otm8009A::health_check`] that tripped
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HealthFault<E> {
+    /// A status read failed on the DSI link
+    NoResponse(E),
+    /// RDDPM differs from the power state the driver put the panel in
+    PowerMode {
+        expected: PowerMode,
+        actual: PowerMode,
+    },
+    /// RDDSDR register loading or functionality detection failed
+    SelfDiagnostic(SelfDiagnostic),
+    /// RDDMADCTL differs from the configured MADCTR
+    AddressMode { expected: u8, actual: u8 },
+    /// RDDCOLMOD differs from the configured pixel format
+    PixelFormat { expected: u8, actual: u8 },
+}
+
+impl Otm8009A {
+    /// Compares the panel state with the configured one
+    ///
+    /// Checks, in order, the power mode (booster, sleep out, idle, partial and
+    /// display on), the self-diagnostic result, MADCTR and COLMOD, and returns
+    /// the first one that does not match, `None` if all do. A failed read ends
+    /// the check with [`HealthFault::NoResponse`].
+    pub fn health_check<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<Option<HealthFault<D::Error>>, Error<D::Error>> {
+        let config = self.config.ok_or(Error::PowerState)?;
+        match self.compare_state(dsi, &config) {
+            Err(Error::Dsi(e)) => Ok(Some(HealthFault::NoResponse(e))),
+            result => result,
+        }
+    }
+
+    fn compare_state<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        config: &Otm8009AConfig,
+    ) -> Result<Option<HealthFault<D::Error>>, Error<D::Error>> {
+        let awake = !self.sleeping;
+        let expected = PowerMode {
+            booster_on: awake,
+            idle_mode: self.idle,
+            partial_mode: self.partial,
+            sleep_out: awake,
+            normal_mode: !self.partial,
+            display_on: self.display_on,
+        };
+        let actual = self.power_mode(dsi)?;
+        if actual != expected {
+            return Ok(Some(HealthFault::PowerMode { expected, actual }));
+        }
+
+        let diagnostic = self.self_diagnostic(dsi)?;
+        if diagnostic.register_loading != self.diagnostic_level
+            || diagnostic.functionality != self.diagnostic_level
+        {
+            return Ok(Some(HealthFault::SelfDiagnostic(diagnostic)));
+        }
+
+        let expected = config.madctr();
+        let actual = read_byte(dsi, regs::OTM8009A_CMD_RDDMADCTL)?;
+        if actual != expected {
+            return Ok(Some(HealthFault::AddressMode { expected, actual }));
+        }
+
+        let expected = config.pixel_format as u8;
+        let actual = read_byte(dsi, regs::OTM8009A_CMD_RDDCOLMOD)?;
+        if actual != expected {
+            return Ok(Some(HealthFault::PixelFormat { expected, actual }));
+        }
+        Ok(None)
+    }
+
+    /// Runs [`health_check`](Self::health_check) and re-initialises the panel
+    /// if it fails
+    ///
+    /// The panel is reset with SWRESET and brought up again with the stored
+    /// configuration and init sequence, so it ends up displaying with the
+    /// `init` defaults for brightness and CABC. Returns the fault that
+    /// triggered the recovery, `None` if the panel was healthy.
+    pub fn recover<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        delay: &mut impl DelayMs<u32>,
+    ) -> Result<Option<HealthFault<D::Error>>, Error<D::Error>> {
+        let Some(fault) = self.health_check(dsi)? else {
+            return Ok(None);
+        };
+        let config = self.config.ok_or(Error::PowerState)?;
+        write_short(dsi, regs::OTM8009A_CMD_SWRESET, 0)?;
+        delay.delay_ms(SWRESET_DELAY_MS);
+        self.init_with_sequence(dsi, config, self.sequence, delay)?;
+        Ok(Some(fault))
+    }
+}
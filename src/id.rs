@@ -0,0 +1,57 @@
+//! Panel identification readout
+
+use crate::otm8009a::{read_byte, read_dcs, OTM8009A_ID1};
+use crate::{regs, Error, Otm8009A};
+use embedded_display_controller::dsi::DsiHostCtrlIo;
+
+/// Device descriptor block (RDDDB)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Ddb {
+    pub supplier_id: u16,
+    pub supplier_elective_data: u16,
+}
+
+/// Identification registers of the panel
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PanelId {
+    /// Manufacturer ID (RDID1)
+    pub id1: u8,
+    /// Module / driver version ID (RDID2)
+    pub id2: u8,
+    /// Module ID (RDID3)
+    pub id3: u8,
+    /// ID1, ID2 and ID3 as returned by RDDID
+    pub rddid: [u8; 3],
+    pub ddb: Ddb,
+}
+
+impl PanelId {
+    /// Whether ID1 is the OTM8009A manufacturer ID
+    pub fn is_otm8009a(&self) -> bool {
+        self.id1 == OTM8009A_ID1
+    }
+}
+
+impl Otm8009A {
+    /// Reads ID1, ID2, ID3, RDDID and the DDB supplier block
+    pub fn read_id<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<PanelId, Error<D::Error>> {
+        let id1 = read_byte(dsi, regs::OTM8009A_CMD_ID1)?;
+        let id2 = read_byte(dsi, regs::OTM8009A_CMD_ID2)?;
+        let id3 = read_byte(dsi, regs::OTM8009A_CMD_ID3)?;
+        let mut rddid = [0u8; 3];
+        read_dcs(dsi, regs::OTM8009A_CMD_RDDID, &mut rddid)?;
+        // Supplier ID and supplier elective data, MSB first, then the 0xFF exit code
+        let mut ddb = [0u8; 5];
+        read_dcs(dsi, regs::OTM8009A_CMD_RDDDB, &mut ddb)?;
+        Ok(PanelId {
+            id1,
+            id2,
+            id3,
+            rddid,
+            ddb: Ddb {
+                supplier_id: u16::from_be_bytes([ddb[0], ddb[1]]),
+                supplier_elective_data: u16::from_be_bytes([ddb[2], ddb[3]]),
+            },
+        })
+    }
+}
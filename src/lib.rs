@@ -1,12 +1,44 @@
 #![no_std]
 
+#[cfg(feature = "mock")]
+extern crate std;
+
 #[allow(dead_code)]
 mod regs;
 #[rustfmt::skip]
 pub mod otm8009a;
-pub use otm8009a::Otm8009A;
+pub use self::otm8009a::Otm8009A;
+mod cabc;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod sequence;
+pub use cabc::{CabcMode, DisplayControl};
+mod power;
+pub use power::PowerState;
+mod gram;
+pub use gram::Window;
+mod memtest;
+pub use memtest::{MemoryReport, Mismatch, TestPattern};
+mod id;
+pub use id::{Ddb, PanelId};
+mod status;
+pub use status::{
+    AddressMode, DisplayStatus, DsiErrorCount, ImageMode, PowerMode, SelfDiagnostic, SignalMode,
+};
+mod health;
+pub use health::HealthFault;
+mod tearing;
+pub use tearing::TearingEffectMode;
+mod gamma;
+pub use gamma::{DigitalGamma, Gamma, GammaError, DIGITAL_GAMMA_POINTS, GAMMA_ENTRY_MAX};
+mod voltage;
+pub use voltage::{Millivolts, DEFAULT_GVDD, DEFAULT_NGVDD, DEFAULT_VCOM, GVDD_RANGE, VCOM_RANGE};
+#[cfg(feature = "embedded-graphics")]
+pub mod graphics;
+pub mod typestate;
 
 #[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum FrameRate {
     _35Hz = 0b000,
     _40Hz = 0b001,
@@ -18,22 +50,235 @@ pub enum FrameRate {
     _70Hz = 0b111,
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Mode {
     Portrait,
     Landscape,
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rotation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+/// Memory access orientation: a rotation, then optional mirroring
+///
+/// Flips are applied in the rotated frame, so `flip_horizontal` always mirrors
+/// along the `cols` axis of the configuration.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Orientation {
+    pub rotation: Rotation,
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+}
+
+impl Orientation {
+    pub const fn new(rotation: Rotation) -> Self {
+        Orientation {
+            rotation,
+            flip_horizontal: false,
+            flip_vertical: false,
+        }
+    }
+
+    /// Returns `true` when columns and rows are exchanged relative to portrait
+    pub const fn swaps_axes(&self) -> bool {
+        matches!(self.rotation, Rotation::Deg90 | Rotation::Deg270)
+    }
+
+    /// MY, MX and MV bits of MADCTR
+    pub(crate) const fn madctr(&self) -> u8 {
+        let (mut mx, mut my) = match self.rotation {
+            Rotation::Deg0 => (false, false),
+            Rotation::Deg90 => (true, false),
+            Rotation::Deg180 => (true, true),
+            Rotation::Deg270 => (false, true),
+        };
+        // With MV set the column axis of the configuration is the panel's row axis
+        if self.swaps_axes() {
+            my ^= self.flip_horizontal;
+            mx ^= self.flip_vertical;
+        } else {
+            mx ^= self.flip_horizontal;
+            my ^= self.flip_vertical;
+        }
+        let mut madctr = 0;
+        if my {
+            madctr |= regs::OTM8009A_MADCTR_MY;
+        }
+        if mx {
+            madctr |= regs::OTM8009A_MADCTR_MX;
+        }
+        if self.swaps_axes() {
+            madctr |= regs::OTM8009A_MADCTR_MV;
+        }
+        madctr
+    }
+}
+
+impl From<Mode> for Orientation {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Portrait => Orientation::new(Rotation::Deg0),
+            Mode::Landscape => Orientation::new(Rotation::Deg90),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ColorMap {
     Rgb,
     Bgr,
 }
 
+/// Interface pixel format (COLMOD)
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PixelFormat {
+    Rgb565 = regs::OTM8009A_COLMOD_RGB565,
+    Rgb666 = regs::OTM8009A_COLMOD_RGB666,
+    Rgb888 = regs::OTM8009A_COLMOD_RGB888,
+    /// RGB888 sent in three transfers per pixel
+    Rgb888_3T = regs::OTM8009A_COLMOD_RGB888_3T,
+}
+
+impl PixelFormat {
+    /// Bytes per pixel in GRAM write and read data
+    pub const fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormat::Rgb565 => 2,
+            _ => 3,
+        }
+    }
+
+    /// Decodes a COLMOD / RDDCOLMOD value
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            regs::OTM8009A_COLMOD_RGB565 => Some(PixelFormat::Rgb565),
+            regs::OTM8009A_COLMOD_RGB666 => Some(PixelFormat::Rgb666),
+            regs::OTM8009A_COLMOD_RGB888 => Some(PixelFormat::Rgb888),
+            regs::OTM8009A_COLMOD_RGB888_3T => Some(PixelFormat::Rgb888_3T),
+            _ => None,
+        }
+    }
+}
+
+/// GRAM columns in portrait orientation
+pub const GRAM_COLS: u16 = 480;
+/// GRAM rows in portrait orientation
+pub const GRAM_ROWS: u16 = 800;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Otm8009AConfig {
     pub frame_rate: FrameRate,
-    pub mode: Mode,
+    /// Frame rate in idle mode, `None` to use `frame_rate`
+    pub idle_frame_rate: Option<FrameRate>,
+    pub orientation: Orientation,
     pub color_map: ColorMap,
+    pub pixel_format: PixelFormat,
     pub cols: u16,
     pub rows: u16,
+    /// Analog gamma tables written to 0xE1 / 0xE2
+    pub gamma: Gamma,
+    /// Per-channel digital gamma, `None` to keep the panel default
+    pub digital_gamma: Option<DigitalGamma>,
+    /// Common electrode voltage, [`DEFAULT_VCOM`] in the reference init
+    pub vcom: Millivolts,
+    /// Positive gamma reference voltage, [`DEFAULT_GVDD`] in the reference init
+    pub gvdd: Millivolts,
+    /// Negative gamma reference voltage, [`DEFAULT_NGVDD`] in the reference init
+    pub ngvdd: Millivolts,
+}
+
+impl Otm8009AConfig {
+    /// Checks that the configuration fits the panel
+    ///
+    /// Called by `init` before any DSI traffic.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.cols == 0 || self.rows == 0 {
+            return Err(ConfigError::ZeroSize);
+        }
+        if self.cols > GRAM_ROWS || self.rows > GRAM_ROWS {
+            return Err(ConfigError::TooLarge);
+        }
+        let (max_cols, max_rows) = if self.orientation.swaps_axes() {
+            (GRAM_ROWS, GRAM_COLS)
+        } else {
+            (GRAM_COLS, GRAM_ROWS)
+        };
+        if self.cols > max_cols || self.rows > max_rows {
+            return Err(ConfigError::OrientationMismatch);
+        }
+        if !self.gamma.is_valid() {
+            return Err(ConfigError::GammaRange);
+        }
+        voltage::validate(self.vcom, self.gvdd, self.ngvdd)
+    }
+
+    // Register codes of a validated configuration
+
+    pub(crate) fn vcom_code(&self) -> u8 {
+        self.vcom.vcom_code().unwrap_or_default()
+    }
+
+    pub(crate) fn gvdd_code(&self) -> u8 {
+        self.gvdd.gvdd_code().unwrap_or_default()
+    }
+
+    pub(crate) fn ngvdd_code(&self) -> u8 {
+        self.ngvdd.ngvdd_code().unwrap_or_default()
+    }
+
+    /// MADCTR value for the orientation and colour order
+    pub(crate) fn madctr(&self) -> u8 {
+        match self.color_map {
+            ColorMap::Rgb => self.orientation.madctr(),
+            ColorMap::Bgr => self.orientation.madctr() | regs::OTM8009A_MADCTR_BGR,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConfigError {
+    /// `cols` or `rows` is zero
+    ZeroSize,
+    /// `cols` or `rows` exceeds the GRAM in any orientation
+    TooLarge,
+    /// Resolution does not fit the GRAM in the chosen orientation
+    OrientationMismatch,
+    /// A gamma table entry exceeds [`GAMMA_ENTRY_MAX`]
+    GammaRange,
+    /// `vcom` is outside [`VCOM_RANGE`]
+    VcomRange,
+    /// `gvdd` or `ngvdd` is outside [`GVDD_RANGE`]
+    GvddRange,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error<E> {
+    /// DSI host transfer failed
+    Dsi(E),
+    /// Panel answered with an unexpected ID1
+    WrongPanelId(u8),
+    /// Configuration cannot be applied to the panel
+    InvalidConfig(ConfigError),
+    /// Data read back differs from what was written
+    VerifyFailed,
+    /// Read buffer length is not supported by the command
+    ReadLength,
+    /// Write data length is not supported by the command
+    WriteLength,
+    /// Call is not allowed in the current power state
+    PowerState,
+    /// Coordinates lie outside the configured `cols` x `rows` area
+    OutOfBounds,
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(e: E) -> Self {
+        Error::Dsi(e)
+    }
 }
@@ -0,0 +1,204 @@
+//! GRAM self-test
+//!
+//! Every pattern is written over the whole test region in one RAMWR/RAMWRC
+//! stream and read back with RAMRD/RAMRDC. The interface is switched to RGB888
+//! for the duration of the test so every written bit is compared. The
+//! configured pixel format and the full-screen window are restored afterwards,
+//! whether the test completed or not.
+
+use crate::otm8009a::{write_short, write_window};
+use crate::{regs, Error, Otm8009A, Otm8009AConfig, PixelFormat, Window};
+use embedded_display_controller::dsi::{DsiHostCtrlIo, DsiReadCommand, DsiWriteCommand};
+
+/// Pixels moved per packet, bounded by the stack buffers
+const CHUNK_PIXELS: usize = 84;
+
+/// Data pattern written to GRAM
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TestPattern {
+    /// A single set bit, moving up one position per pixel
+    WalkingOnes,
+    /// A single cleared bit, moving up one position per pixel
+    WalkingZeros,
+    /// 0x555555 and 0xAAAAAA alternating in both directions
+    Checkerboard,
+    /// Row in the high and column in the low 12 bits, catches stuck or
+    /// shorted address lines
+    AddressInData,
+}
+
+impl TestPattern {
+    pub const ALL: [TestPattern; 4] = [
+        TestPattern::WalkingOnes,
+        TestPattern::WalkingZeros,
+        TestPattern::Checkerboard,
+        TestPattern::AddressInData,
+    ];
+
+    /// 24 bit RGB888 value of the `index`th pixel of the region, at `x`, `y`
+    pub fn value(self, index: usize, x: u16, y: u16) -> u32 {
+        let walking_bit = 1 << (index % 24);
+        match self {
+            TestPattern::WalkingOnes => walking_bit,
+            TestPattern::WalkingZeros => !walking_bit & 0xff_ffff,
+            TestPattern::Checkerboard if (x ^ y) & 1 == 0 => 0x55_5555,
+            TestPattern::Checkerboard => 0xaa_aaaa,
+            TestPattern::AddressInData => (u32::from(y) << 12) | u32::from(x),
+        }
+    }
+}
+
+/// First pixel read back differently from what was written
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    pub pattern: TestPattern,
+    pub x: u16,
+    pub y: u16,
+    /// Written RGB888 value
+    pub expected: u32,
+    /// Read back RGB888 value
+    pub actual: u32,
+}
+
+/// Outcome of [`Otm8009A::memory_check`]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MemoryReport {
+    /// Number of mismatching pixels over all patterns
+    pub errors: usize,
+    pub first_mismatch: Option<Mismatch>,
+}
+
+impl MemoryReport {
+    pub fn passed(&self) -> bool {
+        self.errors == 0
+    }
+
+    /// Turns a failed test into [`Error::VerifyFailed`], for callers that only
+    /// need pass or fail
+    pub fn into_result<E>(self) -> Result<(), Error<E>> {
+        if self.passed() {
+            Ok(())
+        } else {
+            Err(Error::VerifyFailed)
+        }
+    }
+}
+
+impl Otm8009A {
+    /// Runs every [`TestPattern`] over `region` and compares the readback
+    ///
+    /// `max_packet_size` bounds both the long writes (DCS command included)
+    /// and the read return packets. GRAM content inside `region` is
+    /// overwritten. Only DSI and argument errors are returned as `Err`, data
+    /// mismatches are counted in the report, which
+    /// [`into_result`](MemoryReport::into_result) turns into an error.
+    pub fn memory_check<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        region: Window,
+        max_packet_size: u16,
+    ) -> Result<MemoryReport, Error<D::Error>> {
+        let config = self.config.ok_or(Error::PowerState)?;
+        let chunk_pixels = (usize::from(max_packet_size).saturating_sub(1) / 3).min(CHUNK_PIXELS);
+        if chunk_pixels == 0 {
+            return Err(Error::WriteLength);
+        }
+        self.check_gram_access()?;
+        self.check_window(region)?;
+
+        let result = run_patterns(dsi, config.pixel_format, region, chunk_pixels);
+        let restored = restore(dsi, &config);
+        let report = result?;
+        restored?;
+        Ok(report)
+    }
+}
+
+fn run_patterns<D: DsiHostCtrlIo>(
+    dsi: &mut D,
+    pixel_format: PixelFormat,
+    region: Window,
+    chunk_pixels: usize,
+) -> Result<MemoryReport, Error<D::Error>> {
+    if pixel_format != PixelFormat::Rgb888 {
+        write_short(dsi, regs::OTM8009A_CMD_COLMOD, PixelFormat::Rgb888 as u8)?;
+    }
+    let mut report = MemoryReport::default();
+    for pattern in TestPattern::ALL {
+        write_window(dsi, region.x0, region.y0, region.x1, region.y1)?;
+        check_pattern(dsi, region, pattern, chunk_pixels, &mut report)?;
+    }
+    Ok(report)
+}
+
+/// Puts back the configured pixel format and the full-screen window
+fn restore<D: DsiHostCtrlIo>(dsi: &mut D, config: &Otm8009AConfig) -> Result<(), Error<D::Error>> {
+    if config.pixel_format != PixelFormat::Rgb888 {
+        write_short(dsi, regs::OTM8009A_CMD_COLMOD, config.pixel_format as u8)?;
+    }
+    write_window(dsi, 0, 0, config.cols - 1, config.rows - 1)
+}
+
+/// Coordinates of the `index`th pixel of `region`
+fn position(region: Window, index: usize) -> (u16, u16) {
+    let width = usize::from(region.x1 - region.x0) + 1;
+    (
+        region.x0 + (index % width) as u16,
+        region.y0 + (index / width) as u16,
+    )
+}
+
+fn check_pattern<D: DsiHostCtrlIo>(
+    dsi: &mut D,
+    region: Window,
+    pattern: TestPattern,
+    chunk_pixels: usize,
+    report: &mut MemoryReport,
+) -> Result<(), Error<D::Error>> {
+    let pixel_count = region.pixel_count();
+    let mut buf = [0u8; CHUNK_PIXELS * 3];
+
+    let mut arg = regs::OTM8009A_CMD_RAMWR;
+    for start in (0..pixel_count).step_by(chunk_pixels) {
+        let end = (start + chunk_pixels).min(pixel_count);
+        for (index, out) in (start..end).zip(buf.chunks_exact_mut(3)) {
+            let (x, y) = position(region, index);
+            out.copy_from_slice(&pattern.value(index, x, y).to_be_bytes()[1..]);
+        }
+        dsi.write(DsiWriteCommand::DcsLongWrite {
+            arg,
+            data: &buf[..(end - start) * 3],
+        })?;
+        arg = regs::OTM8009A_CMD_RAMWRC;
+    }
+
+    let mut arg = regs::OTM8009A_CMD_RAMRD;
+    let mut return_size = 0;
+    for start in (0..pixel_count).step_by(chunk_pixels) {
+        let end = (start + chunk_pixels).min(pixel_count);
+        let read_len = (end - start) * 3;
+        if read_len != return_size {
+            return_size = read_len;
+            dsi.write(DsiWriteCommand::SetMaximumReturnPacketSize(read_len as u16))?;
+        }
+        dsi.read(DsiReadCommand::DcsShort { arg }, &mut buf[..read_len])?;
+        arg = regs::OTM8009A_CMD_RAMRDC;
+
+        for (index, rgb) in (start..end).zip(buf.chunks_exact(3)) {
+            let (x, y) = position(region, index);
+            let expected = pattern.value(index, x, y);
+            let actual = u32::from_be_bytes([0, rgb[0], rgb[1], rgb[2]]);
+            if actual != expected {
+                report.errors += 1;
+                report.first_mismatch.get_or_insert(Mismatch {
+                    pattern,
+                    x,
+                    y,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,222 @@
+//! Recording DSI host for exercising the driver off-target
+//!
+//! [`MockDsi`] implements [`DsiHostCtrlIo`] and records every transaction in
+//! order. Read commands are answered from a queue of scripted replies, and an
+//! error can be injected at any transaction. [`MockDelay`] shares the same log
+//! so delays show up in between the DSI traffic they belong to.
+
+use core::cell::RefCell;
+use embedded_display_controller::dsi::{DsiHostCtrlIo, DsiReadCommand, DsiWriteCommand};
+use embedded_hal::blocking::delay::DelayMs;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::vec::Vec;
+
+/// Owned copy of a [`DsiWriteCommand`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WriteCommand {
+    DcsShortP0 { arg: u8 },
+    DcsShortP1 { arg: u8, data: u8 },
+    DcsLongWrite { arg: u8, data: Vec<u8> },
+    GenericShortP0,
+    GenericShortP1,
+    GenericShortP2,
+    GenericLongWrite { arg: u8, data: Vec<u8> },
+    SetMaximumReturnPacketSize(u16),
+}
+
+impl From<&DsiWriteCommand<'_>> for WriteCommand {
+    fn from(command: &DsiWriteCommand) -> Self {
+        match *command {
+            DsiWriteCommand::DcsShortP0 { arg } => WriteCommand::DcsShortP0 { arg },
+            DsiWriteCommand::DcsShortP1 { arg, data } => WriteCommand::DcsShortP1 { arg, data },
+            DsiWriteCommand::DcsLongWrite { arg, data } => WriteCommand::DcsLongWrite {
+                arg,
+                data: data.to_vec(),
+            },
+            DsiWriteCommand::GenericShortP0 => WriteCommand::GenericShortP0,
+            DsiWriteCommand::GenericShortP1 => WriteCommand::GenericShortP1,
+            DsiWriteCommand::GenericShortP2 => WriteCommand::GenericShortP2,
+            DsiWriteCommand::GenericLongWrite { arg, data } => WriteCommand::GenericLongWrite {
+                arg,
+                data: data.to_vec(),
+            },
+            DsiWriteCommand::SetMaximumReturnPacketSize(size) => {
+                WriteCommand::SetMaximumReturnPacketSize(size)
+            }
+        }
+    }
+}
+
+/// Owned copy of a [`DsiReadCommand`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadCommand {
+    DcsShort { arg: u8 },
+    GenericShortP0,
+    GenericShortP1 { arg0: u8 },
+    GenericShortP2 { arg0: u8, arg1: u8 },
+}
+
+impl From<&DsiReadCommand> for ReadCommand {
+    fn from(command: &DsiReadCommand) -> Self {
+        match *command {
+            DsiReadCommand::DcsShort { arg } => ReadCommand::DcsShort { arg },
+            DsiReadCommand::GenericShortP0 => ReadCommand::GenericShortP0,
+            DsiReadCommand::GenericShortP1 { arg0 } => ReadCommand::GenericShortP1 { arg0 },
+            DsiReadCommand::GenericShortP2 { arg0, arg1 } => {
+                ReadCommand::GenericShortP2 { arg0, arg1 }
+            }
+        }
+    }
+}
+
+/// One entry of the recorded log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transaction {
+    Write(WriteCommand),
+    /// Read command together with the length of the buffer it was given
+    Read {
+        command: ReadCommand,
+        len: usize,
+    },
+    /// `DelayMs::delay_ms` call, in milliseconds
+    Delay(u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MockError {
+    /// Error injected with [`MockDsi::fail_at`]
+    Injected,
+    /// A read was issued but no reply was scripted
+    NoReply,
+}
+
+#[derive(Default)]
+struct State {
+    log: Vec<Transaction>,
+    replies: VecDeque<Vec<u8>>,
+    fail_at: Option<usize>,
+    count: usize,
+}
+
+impl State {
+    fn next(&mut self) -> Result<(), MockError> {
+        let n = self.count;
+        self.count += 1;
+        if self.fail_at == Some(n) {
+            Err(MockError::Injected)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Recording [`DsiHostCtrlIo`] implementation
+///
+/// Clones share the same log, so a test can keep a handle while the driver
+/// borrows another one.
+#[derive(Clone, Default)]
+pub struct MockDsi {
+    state: Rc<RefCell<State>>,
+}
+
+impl MockDsi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a delay provider that records into the same log
+    pub fn delay(&self) -> MockDelay {
+        MockDelay {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Queues a reply for the next read command
+    ///
+    /// Replies are served in FIFO order. A reply shorter than the read buffer
+    /// leaves the remaining bytes zeroed, a longer one is truncated.
+    pub fn push_reply(&self, data: &[u8]) {
+        self.state.borrow_mut().replies.push_back(data.to_vec());
+    }
+
+    /// Makes the `n`th DSI transaction (counting from zero, reads and writes
+    /// alike) fail with [`MockError::Injected`]
+    ///
+    /// The failing transaction is not recorded.
+    pub fn fail_at(&self, n: usize) {
+        self.state.borrow_mut().fail_at = Some(n);
+    }
+
+    /// Returns every recorded transaction, in order
+    pub fn transactions(&self) -> Vec<Transaction> {
+        self.state.borrow().log.clone()
+    }
+
+    /// Returns the recorded write commands only, in order
+    pub fn writes(&self) -> Vec<WriteCommand> {
+        self.state
+            .borrow()
+            .log
+            .iter()
+            .filter_map(|t| match t {
+                Transaction::Write(w) => Some(w.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the recorded delays only, in order
+    pub fn delays(&self) -> Vec<u32> {
+        self.state
+            .borrow()
+            .log
+            .iter()
+            .filter_map(|t| match t {
+                Transaction::Delay(ms) => Some(*ms),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Forgets the recorded log, pending replies and injected error
+    pub fn clear(&self) {
+        *self.state.borrow_mut() = State::default();
+    }
+}
+
+impl DsiHostCtrlIo for MockDsi {
+    type Error = MockError;
+
+    fn write(&mut self, command: DsiWriteCommand) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        state.next()?;
+        state.log.push(Transaction::Write((&command).into()));
+        Ok(())
+    }
+
+    fn read(&mut self, command: DsiReadCommand, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        state.next()?;
+        state.log.push(Transaction::Read {
+            command: (&command).into(),
+            len: buf.len(),
+        });
+        let reply = state.replies.pop_front().ok_or(MockError::NoReply)?;
+        buf.fill(0);
+        let n = reply.len().min(buf.len());
+        buf[..n].copy_from_slice(&reply[..n]);
+        Ok(())
+    }
+}
+
+/// Recording [`DelayMs`] implementation, see [`MockDsi::delay`]
+pub struct MockDelay {
+    state: Rc<RefCell<State>>,
+}
+
+impl DelayMs<u32> for MockDelay {
+    fn delay_ms(&mut self, ms: u32) {
+        self.state.borrow_mut().log.push(Transaction::Delay(ms));
+    }
+}
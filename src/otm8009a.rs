@@ -1,12 +1,49 @@
-use crate::{regs, ColorMap, Mode, Otm8009AConfig};
+use crate::gamma::write_gamma;
+use crate::sequence::{InitStep, CONFIG_LONG_MAX, DEFAULT_SEQUENCE};
+use crate::{regs, ColorMap, Error, Orientation, Otm8009AConfig, PixelFormat, TearingEffectMode};
 use embedded_display_controller::dsi::{DsiHostCtrlIo, DsiReadCommand, DsiWriteCommand};
 use embedded_hal::blocking::delay::DelayMs;
 
-pub struct Otm8009A {}
+/// ID1 (manufacturer ID) of the OTM8009A
+pub(crate) const OTM8009A_ID1: u8 = 0x40;
+
+/// CMD2 access key written to 0xFF00 (and its first two bytes to 0xFF80)
+const CMD2_KEY: [u8; 3] = [0x80, 0x09, 0x01];
+
+#[derive(Debug)]
+pub struct Otm8009A {
+    cmd2_unlocked: bool,
+    /// Configuration of the last successful `init`, kept up to date by the runtime setters
+    pub(crate) config: Option<Otm8009AConfig>,
+    /// Vendor sequence of the last successful `init`
+    pub(crate) sequence: &'static [InitStep],
+    /// Expected RDDSDR register loading and functionality bits, the panel
+    /// inverts both on every successful sleep out after a reset
+    pub(crate) diagnostic_level: bool,
+    pub(crate) sleeping: bool,
+    pub(crate) display_on: bool,
+    pub(crate) idle: bool,
+    pub(crate) partial: bool,
+}
+
+impl Default for Otm8009A {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Otm8009A {
     pub fn new() -> Self {
-        Otm8009A {}
+        Otm8009A {
+            cmd2_unlocked: false,
+            config: None,
+            sequence: DEFAULT_SEQUENCE,
+            diagnostic_level: false,
+            sleeping: true,
+            display_on: false,
+            idle: false,
+            partial: false,
+        }
     }
 
     pub fn init<D: DsiHostCtrlIo>(
@@ -14,449 +51,21 @@ impl Otm8009A {
         dsi: &mut D,
         config: Otm8009AConfig,
         delay: &mut impl DelayMs<u32>,
-    ) -> Result<(), D::Error> {
-        // Enable CMD2 to access vendor specific commands
-        // Enter in command 2 mode and set EXTC to enable address shift function (0x00)
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x00,
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xff,
-            data: &[0x80, 0x09, 0x01],
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x80,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xff,
-            data: &[0x80, 0x09],
-        })?;
-
-        // SD_PCH_CTRL - 0xC480h - 129th parameter - Default 0x00
-        // Set SD_PT
-        // Source output level during porch and non-display area to GND
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x80,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc4,
-            data: 0x30,
-        })?;
-
-        delay.delay_ms(10);
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x8a,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc4,
-            data: 0x40,
-        })?;
-
-        delay.delay_ms(10);
-
-        // PWR_CTRL4 - 0xC4B0h - 178th parameter - Default 0xA8
-        // Set gvdd_en_test
-        // -> enable GVDD test mode !!!
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xb1,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc5,
-            data: 0xa9,
-        })?;
-
-        // PWR_CTRL2 - 0xC590h - 146th parameter - Default 0x79
-        // Set pump 4 vgh voltage
-        // -> from 15.0v down to 13.0v
-        // Set pump 5 vgh voltage
-        // -> from -12.0v downto -9.0v
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x91,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc5,
-            data: 0x34,
-        })?;
-
-        // P_DRV_M - 0xC0B4h - 181th parameter - Default 0x00
-        // -> Column inversion
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xb4,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc0,
-            data: 0x50,
-        })?;
-
-        // VCOMDC - 0xD900h - 1st parameter - Default 0x39h
-        // VCOM Voltage settings
-        // -> from -1.0000v downto -1.2625v
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x00,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xd9,
-            data: 0x4e,
-        })?;
-
-        // Oscillator adjustment for Idle/Normal mode (LPDT only) set to 65Hz (default is 60Hz) *
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x81,
-        })?;
-        let frame_rate = config.frame_rate as u8;
-        let frame_rate = frame_rate | (frame_rate << 4);
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc1,
-            data: frame_rate,
-        })?;
-
-        // Video mode internal
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xa1,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc1,
-            data: 0x08,
-        })?;
-
-        // PWR_CTRL2 - 0xC590h - 147h parameter - Default 0x00
-        // Set pump 4&5 x6
-        // -> ONLY VALID when PUMP4_EN_ASDM_HV = "0"
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x92,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc5,
-            data: 0x01,
-        })?;
-
-        // PWR_CTRL2 - 0xC590h - 150th parameter - Default 0x33h
-        // Change pump4 clock ratio
-        // -> from 1 line to 1/2 line
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x95,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc5,
-            data: 0x34,
-        })?;
-
-        // GVDD/NGVDD settings
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x00,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xd8,
-            data: &[0x79, 0x79],
-        })?;
-
-        // PWR_CTRL2 - 0xC590h - 149th parameter - Default 0x33h
-        // Rewrite the default value !
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x94,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc5,
-            data: 0x33,
-        })?;
-
-        // Panel display timing Setting 3
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xa3,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc0,
-            data: 0x1b,
-        })?;
-
-        // Power control 1 *
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x82,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc5,
-            data: 0x83,
-        })?;
-
-        //   /* Source driver precharge */
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x81,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc4,
-            data: 0x83,
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xa1,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc1,
-            data: 0x0e,
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xa6,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xb3,
-            data: &[0x00, 0x01],
-        })?;
-
-        // GOAVST
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x80,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xce,
-            data: &[0x85, 0x01, 0x00, 0x84, 0x01, 0x00],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xa0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xce,
-            data: &[
-                0x18, 0x04, 0x03, 0x39, 0x00, 0x00, 0x00, 0x18, 0x03, 0x03, 0x3A, 0x00, 0x00, 0x00,
-            ],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xb0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xce,
-            data: &[
-                0x18, 0x02, 0x03, 0x3B, 0x00, 0x00, 0x00, 0x18, 0x01, 0x03, 0x3C, 0x00, 0x00, 0x00,
-            ],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xc0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcf,
-            data: &[0x01, 0x01, 0x20, 0x20, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xd0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xcf,
-            data: 0x00,
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x80,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcb,
-            data: &[0u8; 10],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x90,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcb,
-            data: &[0u8; 15],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xa0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcb,
-            data: &[0u8; 15],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xb0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcb,
-            data: &[0u8; 10],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xc0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcb,
-            data: &[
-                0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-                0x00,
-            ],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xd0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcb,
-            data: &[
-                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00,
-                0x00,
-            ],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xe0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcb,
-            data: &[0u8; 10],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xf0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcb,
-            data: &[255u8; 10],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x80,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcc,
-            data: &[0x00, 0x26, 0x09, 0x0B, 0x01, 0x25, 0x00, 0x00, 0x00, 0x00],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x90,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcc,
-            data: &[
-                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x26, 0x0A, 0x0C,
-                0x02,
-            ],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xa0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcc,
-            data: &[
-                0x25, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-                0x00,
-            ],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xb0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcc,
-            data: &[0x00, 0x25, 0x0C, 0x0A, 0x02, 0x26, 0x00, 0x00, 0x00, 0x00],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xc0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcc,
-            data: &[
-                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x25, 0x0B, 0x09,
-                0x01,
-            ],
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xd0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xcc,
-            data: &[
-                0x26, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-                0x00,
-            ],
-        })?;
-
-        // PWR_CTRL1 - 0xc580h - 130th parameter - default
-        // Pump 1 min and max DM
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x81,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc5,
-            data: 0x66,
-        })?;
-
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xb6,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xf5,
-            data: 0x06,
-        })?;
-
-        // CABC LEDPWM frequency adjusted to 19,5kHz
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0xb1,
-        })?;
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: 0xc6,
-            data: 0x06,
-        })?;
+    ) -> Result<(), Error<D::Error>> {
+        self.init_with_sequence(dsi, config, DEFAULT_SEQUENCE, delay)
+    }
 
-        // Exit CMD2 mode
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xff,
-            data: &[0xff, 0xff, 0xff],
-        })?;
+    /// Same as [`init`](Self::init), but runs `sequence` instead of the
+    /// [`DEFAULT_SEQUENCE`] for the vendor (CMD2) part of the bring-up
+    pub fn init_with_sequence<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        config: Otm8009AConfig,
+        sequence: &'static [InitStep],
+        delay: &mut impl DelayMs<u32>,
+    ) -> Result<(), Error<D::Error>> {
+        config.validate().map_err(Error::InvalidConfig)?;
+        self.run_sequence(dsi, &config, sequence, delay)?;
 
         // Standard DCS Initialization TO KEEP CAN BE DONE IN HSDT
         // NOP - goes back to DCS std command ?
@@ -465,31 +74,12 @@ impl Otm8009A {
             data: 0,
         })?;
 
-        // Gamma correction 2.2+ table (HSDT possible)
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xe1,
-            data: &[
-                0x00, 0x09, 0x0F, 0x0E, 0x07, 0x10, 0x0B, 0x0A, 0x04, 0x07, 0x0B, 0x08, 0x0F, 0x10,
-                0x0A, 0x01,
-            ],
-        })?;
+        // Gamma correction positive and negative tables (HSDT possible), 2.2 by default
+        write_gamma(dsi, &config.gamma)?;
 
-        // Gamma correction 2.2- table (HSDT possible)
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_NOP,
-            data: 0x00,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: 0xe2,
-            data: &[
-                0x00, 0x09, 0x0F, 0x0E, 0x07, 0x10, 0x0B, 0x0A, 0x04, 0x07, 0x0B, 0x08, 0x0F, 0x10,
-                0x0A, 0x01,
-            ],
-        })?;
+        if let Some(digital_gamma) = &config.digital_gamma {
+            self.write_digital_gamma(dsi, digital_gamma)?;
+        }
 
         // Send Sleep Out command to display : no parameter
         dsi.write(DsiWriteCommand::DcsShortP1 {
@@ -502,7 +92,7 @@ impl Otm8009A {
 
         dsi.write(DsiWriteCommand::DcsShortP1 {
             arg: regs::OTM8009A_CMD_COLMOD,
-            data: regs::OTM8009A_COLMOD_RGB888,
+            data: config.pixel_format as u8,
         })?;
 
         // Send command to configure display in landscape orientation mode. By default
@@ -517,32 +107,7 @@ impl Otm8009A {
         // XS[15:0] = 0x000 = 0, XE[15:0] = 0x1DF = 479 for portrait mode : apply to CASET
         // YS[15:0] = 0x000 = 0, YE[15:0] = 0x1DF = 479 for landscape mode : apply to PASET
         //static const uint8_t LcdRegData28[] = {0x00, 0x00, 0x01, 0xDF};
-        let madctr = if config.mode == Mode::Portrait {
-            0
-        } else {
-            regs::OTM8009A_MADCTR_MODE_LANDSCAPE // MX=1 MV=1
-        };
-        let madctr = if config.color_map == ColorMap::Rgb {
-            madctr
-        } else {
-            madctr | (1 << 3)
-        };
-        dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_MADCTR,
-            data: madctr,
-        })?;
-        let last_col = (config.cols - 1).to_be_bytes();
-        let last_row = (config.rows - 1).to_be_bytes();
-        let caset = [0, 0, last_col[0], last_col[1]];
-        let paset = [0, 0, last_row[0], last_row[1]];
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: regs::OTM8009A_CMD_CASET,
-            data: &caset,
-        })?;
-        dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: regs::OTM8009A_CMD_PASET,
-            data: &paset,
-        })?;
+        self.write_address_mode(dsi, &config)?;
 
         //* CABC : Content Adaptive Backlight Control section start
         // Note : defaut is 0 (lowest Brightness], 0xFF is highest Brightness, try 0x7F : intermediate value
@@ -588,26 +153,238 @@ impl Otm8009A {
             data: 0,
         })?;
 
+        self.config = Some(config);
+        self.sequence = sequence;
+        self.diagnostic_level = true;
+        self.sleeping = false;
+        self.display_on = true;
+        self.idle = false;
+        self.partial = false;
         Ok(())
     }
 
-    pub fn enable_te_output<D: DsiHostCtrlIo>(
+    /// Returns the configuration in effect, `None` before `init`
+    pub fn config(&self) -> Option<&Otm8009AConfig> {
+        self.config.as_ref()
+    }
+
+    /// Changes the interface pixel format (COLMOD)
+    pub fn set_pixel_format<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        pixel_format: PixelFormat,
+    ) -> Result<(), Error<D::Error>> {
+        let config = self.config.as_mut().ok_or(Error::PowerState)?;
+        dsi.write(DsiWriteCommand::DcsShortP1 {
+            arg: regs::OTM8009A_CMD_COLMOD,
+            data: pixel_format as u8,
+        })?;
+        config.pixel_format = pixel_format;
+        Ok(())
+    }
+
+    /// Changes the orientation, keeping the colour order
+    ///
+    /// `cols` and `rows` are exchanged when the new orientation swaps axes
+    /// relative to the current one, then MADCTR and the CASET/PASET window are
+    /// rewritten. The new orientation is kept as soon as MADCTR is written, so
+    /// it still matches the panel if the window write fails.
+    pub fn set_orientation<D: DsiHostCtrlIo>(
         &mut self,
-        on_line: u16,
         dsi: &mut D,
-    ) -> Result<(), D::Error> {
+        orientation: Orientation,
+    ) -> Result<(), Error<D::Error>> {
+        let mut config = self.config.ok_or(Error::PowerState)?;
+        if config.orientation.swaps_axes() != orientation.swaps_axes() {
+            core::mem::swap(&mut config.cols, &mut config.rows);
+        }
+        config.orientation = orientation;
+        config.validate().map_err(Error::InvalidConfig)?;
+        dsi.write(DsiWriteCommand::DcsShortP1 {
+            arg: regs::OTM8009A_CMD_MADCTR,
+            data: config.madctr(),
+        })?;
+        self.config = Some(config);
+        write_window(dsi, 0, 0, config.cols - 1, config.rows - 1)
+    }
+
+    /// Changes the colour order, keeping the orientation
+    pub fn set_color_map<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        color_map: ColorMap,
+    ) -> Result<(), Error<D::Error>> {
+        let mut config = self.config.ok_or(Error::PowerState)?;
+        config.color_map = color_map;
+        dsi.write(DsiWriteCommand::DcsShortP1 {
+            arg: regs::OTM8009A_CMD_MADCTR,
+            data: config.madctr(),
+        })?;
+        self.config = Some(config);
+        Ok(())
+    }
+
+    /// Writes MADCTR and sets the CASET/PASET window to the whole screen
+    fn write_address_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        config: &Otm8009AConfig,
+    ) -> Result<(), Error<D::Error>> {
+        dsi.write(DsiWriteCommand::DcsShortP1 {
+            arg: regs::OTM8009A_CMD_MADCTR,
+            data: config.madctr(),
+        })?;
+        write_window(dsi, 0, 0, config.cols - 1, config.rows - 1)
+    }
+
+    fn run_sequence<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        config: &Otm8009AConfig,
+        sequence: &[InitStep],
+        delay: &mut impl DelayMs<u32>,
+    ) -> Result<(), Error<D::Error>> {
+        for step in sequence {
+            match *step {
+                InitStep::Shift(addr) => self.shift(dsi, addr)?,
+                InitStep::Short(arg, data) => dsi.write(DsiWriteCommand::DcsShortP1 { arg, data })?,
+                InitStep::Long(arg, data) => {
+                    dsi.write(DsiWriteCommand::DcsLongWrite { arg, data })?;
+                    if arg == regs::OTM8009A_CMD_CMD2ENA {
+                        self.cmd2_unlocked = data.starts_with(&CMD2_KEY[..2]);
+                    }
+                }
+                InitStep::Config(arg, param) => dsi.write(DsiWriteCommand::DcsShortP1 {
+                    arg,
+                    data: param.value(config),
+                })?,
+                InitStep::ConfigLong(arg, params) => {
+                    let mut data = [0u8; CONFIG_LONG_MAX];
+                    let data = data.get_mut(..params.len()).ok_or(Error::WriteLength)?;
+                    for (byte, param) in data.iter_mut().zip(params) {
+                        *byte = param.value(config);
+                    }
+                    dsi.write(DsiWriteCommand::DcsLongWrite { arg, data })?;
+                }
+                InitStep::Delay(ms) => delay.delay_ms(ms.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` while the vendor command set (CMD2) is accessible
+    pub fn is_cmd2_unlocked(&self) -> bool {
+        self.cmd2_unlocked
+    }
+
+    /// Enters CMD2 mode, giving access to the vendor specific registers
+    pub fn unlock_cmd2<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<(), Error<D::Error>> {
+        self.shift(dsi, 0x00)?;
         dsi.write(DsiWriteCommand::DcsLongWrite {
-            arg: regs::OTM8009A_CMD_WRTESCN,
-            data: &on_line.to_be_bytes(),
+            arg: regs::OTM8009A_CMD_CMD2ENA,
+            data: &CMD2_KEY,
         })?;
+        self.shift(dsi, 0x80)?;
+        dsi.write(DsiWriteCommand::DcsLongWrite {
+            arg: regs::OTM8009A_CMD_CMD2ENA,
+            data: &CMD2_KEY[..2],
+        })?;
+        self.cmd2_unlocked = true;
+        Ok(())
+    }
+
+    /// Leaves CMD2 mode
+    pub fn lock_cmd2<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<(), Error<D::Error>> {
+        self.shift(dsi, 0x00)?;
+        dsi.write(DsiWriteCommand::DcsLongWrite {
+            arg: regs::OTM8009A_CMD_CMD2ENA,
+            data: &[0xff, 0xff, 0xff],
+        })?;
+        self.cmd2_unlocked = false;
+        Ok(())
+    }
+
+    /// Writes `data` to the vendor register at `addr` (for example 0xC480)
+    ///
+    /// CMD2 is unlocked for the write if needed and locked again afterwards,
+    /// unless it was already unlocked with [`unlock_cmd2`](Self::unlock_cmd2).
+    pub fn write_vendor_reg<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        addr: u16,
+        data: &[u8],
+    ) -> Result<(), Error<D::Error>> {
+        let [arg, shift] = addr.to_be_bytes();
+        self.with_cmd2(dsi, |this, dsi| {
+            this.shift(dsi, shift)?;
+            match *data {
+                [] => dsi.write(DsiWriteCommand::DcsShortP0 { arg })?,
+                [data] => dsi.write(DsiWriteCommand::DcsShortP1 { arg, data })?,
+                _ => dsi.write(DsiWriteCommand::DcsLongWrite { arg, data })?,
+            }
+            Ok(())
+        })
+    }
+
+    /// Reads `buf.len()` bytes from the vendor register at `addr`
+    ///
+    /// CMD2 is handled the same way as in [`write_vendor_reg`](Self::write_vendor_reg).
+    pub fn read_vendor_reg<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        addr: u16,
+        buf: &mut [u8],
+    ) -> Result<(), Error<D::Error>> {
+        let [arg, shift] = addr.to_be_bytes();
+        self.with_cmd2(dsi, |this, dsi| {
+            this.shift(dsi, shift)?;
+            read_dcs(dsi, arg, buf)
+        })
+    }
+
+    /// Runs `f` with CMD2 unlocked, locking it again afterwards unless it was
+    /// unlocked explicitly
+    ///
+    /// The lock is attempted even if the unlock or `f` failed, the first error
+    /// is returned.
+    pub(crate) fn with_cmd2<D: DsiHostCtrlIo, T>(
+        &mut self,
+        dsi: &mut D,
+        f: impl FnOnce(&mut Self, &mut D) -> Result<T, Error<D::Error>>,
+    ) -> Result<T, Error<D::Error>> {
+        if self.cmd2_unlocked {
+            return f(self, dsi);
+        }
+        let result = self.unlock_cmd2(dsi).and_then(|()| f(self, dsi));
+        let locked = self.lock_cmd2(dsi);
+        let value = result?;
+        locked?;
+        Ok(value)
+    }
+
+    /// Address shift: sets the low byte of the next vendor register address
+    fn shift<D: DsiHostCtrlIo>(&mut self, dsi: &mut D, addr: u8) -> Result<(), Error<D::Error>> {
         dsi.write(DsiWriteCommand::DcsShortP1 {
-            arg: regs::OTM8009A_CMD_TEEON,
-            data: regs::OTM8009A_TEEON_TELOM_VBLANKING_INFO_ONLY,
+            arg: regs::OTM8009A_CMD_NOP,
+            data: addr,
         })?;
         Ok(())
     }
 
-    pub fn id_matches<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<bool, D::Error> {
+    /// Raises the TE output on `on_line`, V-blanking information only
+    ///
+    /// Shorthand for [`set_tearing_effect_scanline`](Self::set_tearing_effect_scanline)
+    /// followed by [`enable_tearing_effect`](Self::enable_tearing_effect).
+    pub fn enable_te_output<D: DsiHostCtrlIo>(
+        &mut self,
+        on_line: u16,
+        dsi: &mut D,
+    ) -> Result<(), Error<D::Error>> {
+        self.set_tearing_effect_scanline(dsi, on_line)?;
+        self.enable_tearing_effect(dsi, TearingEffectMode::VBlank)
+    }
+
+    pub fn id_matches<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<(), Error<D::Error>> {
         let mut buf = [0u8; 1];
         dsi.read(
             DsiReadCommand::DcsShort {
@@ -615,29 +392,59 @@ impl Otm8009A {
             },
             &mut buf,
         )?;
-        if buf[0] == 0x40 {
-            Ok(true)
+        if buf[0] == OTM8009A_ID1 {
+            Ok(())
         } else {
-            Ok(false)
+            Err(Error::WrongPanelId(buf[0]))
         }
     }
+}
 
-    pub fn memory_check<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<(), D::Error> {
-        let ramp = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17];
-        let mut buf = [0u8; 17];
-        for i in (1..17).rev() {
-            dsi.write(DsiWriteCommand::DcsLongWrite {
-                arg: regs::OTM8009A_CMD_RAMWR,
-                data: &ramp[..i],
-            })?;
-            dsi.read(
-                DsiReadCommand::DcsShort {
-                    arg: regs::OTM8009A_CMD_RAMRD,
-                },
-                &mut buf[..i],
-            )?;
-            // debug!("read: {:?}", &buf[..i]);
-        }
-        Ok(())
+/// DCS read of `buf.len()` bytes, raising the maximum return packet size when
+/// more than one byte is expected
+pub(crate) fn read_dcs<D: DsiHostCtrlIo>(dsi: &mut D, arg: u8, buf: &mut [u8]) -> Result<(), Error<D::Error>> {
+    let len = u16::try_from(buf.len()).map_err(|_| Error::ReadLength)?;
+    match len {
+        0 => return Err(Error::ReadLength),
+        1 => {}
+        _ => dsi.write(DsiWriteCommand::SetMaximumReturnPacketSize(len))?,
     }
+    dsi.read(DsiReadCommand::DcsShort { arg }, buf)?;
+    Ok(())
+}
+
+/// DCS short write with one parameter
+pub(crate) fn write_short<D: DsiHostCtrlIo>(dsi: &mut D, arg: u8, data: u8) -> Result<(), Error<D::Error>> {
+    dsi.write(DsiWriteCommand::DcsShortP1 { arg, data })?;
+    Ok(())
+}
+
+/// DCS read of a single byte
+pub(crate) fn read_byte<D: DsiHostCtrlIo>(dsi: &mut D, arg: u8) -> Result<u8, Error<D::Error>> {
+    let mut buf = [0u8; 1];
+    read_dcs(dsi, arg, &mut buf)?;
+    Ok(buf[0])
+}
+
+/// CASET and PASET, both ends inclusive
+pub(crate) fn write_window<D: DsiHostCtrlIo>(
+    dsi: &mut D,
+    x0: u16,
+    y0: u16,
+    x1: u16,
+    y1: u16,
+) -> Result<(), Error<D::Error>> {
+    let [xs_hi, xs_lo] = x0.to_be_bytes();
+    let [xe_hi, xe_lo] = x1.to_be_bytes();
+    let [ys_hi, ys_lo] = y0.to_be_bytes();
+    let [ye_hi, ye_lo] = y1.to_be_bytes();
+    dsi.write(DsiWriteCommand::DcsLongWrite {
+        arg: regs::OTM8009A_CMD_CASET,
+        data: &[xs_hi, xs_lo, xe_hi, xe_lo],
+    })?;
+    dsi.write(DsiWriteCommand::DcsLongWrite {
+        arg: regs::OTM8009A_CMD_PASET,
+        data: &[ys_hi, ys_lo, ye_hi, ye_lo],
+    })?;
+    Ok(())
 }
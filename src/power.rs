@@ -0,0 +1,207 @@
+//! Sleep, display on/off, idle and partial mode power management
+
+use crate::otm8009a::write_short;
+use crate::{regs, ConfigError, Error, Otm8009A, GRAM_ROWS};
+use embedded_display_controller::dsi::{DsiHostCtrlIo, DsiWriteCommand};
+use embedded_hal::blocking::delay::DelayMs;
+
+/// Time to wait after SLPOUT before the next SLPIN, and for the panel to wake up
+const SLPOUT_DELAY_MS: u32 = 120;
+/// Time to wait after SLPIN before the next command
+const SLPIN_DELAY_MS: u32 = 5;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PowerState {
+    /// `init` has not completed yet
+    Uninitialized,
+    /// Sleep mode, the display is blank whatever its on/off setting
+    Sleeping,
+    /// Out of sleep with the display off
+    Awake,
+    /// Out of sleep with the display on
+    DisplayOn,
+}
+
+impl Otm8009A {
+    pub fn power_state(&self) -> PowerState {
+        if self.config.is_none() {
+            PowerState::Uninitialized
+        } else if self.sleeping {
+            PowerState::Sleeping
+        } else if self.display_on {
+            PowerState::DisplayOn
+        } else {
+            PowerState::Awake
+        }
+    }
+
+    /// Enters sleep mode (SLPIN)
+    pub fn sleep<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        delay: &mut impl DelayMs<u32>,
+    ) -> Result<(), Error<D::Error>> {
+        if matches!(
+            self.power_state(),
+            PowerState::Uninitialized | PowerState::Sleeping
+        ) {
+            return Err(Error::PowerState);
+        }
+        write_short(dsi, regs::OTM8009A_CMD_SLPIN, 0)?;
+        delay.delay_ms(SLPIN_DELAY_MS);
+        self.sleeping = true;
+        Ok(())
+    }
+
+    /// Leaves sleep mode (SLPOUT)
+    ///
+    /// Waits long enough afterwards for the panel to be ready and for a
+    /// following [`sleep`](Self::sleep) to be valid.
+    pub fn wake<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        delay: &mut impl DelayMs<u32>,
+    ) -> Result<(), Error<D::Error>> {
+        if self.power_state() != PowerState::Sleeping {
+            return Err(Error::PowerState);
+        }
+        write_short(dsi, regs::OTM8009A_CMD_SLPOUT, 0)?;
+        delay.delay_ms(SLPOUT_DELAY_MS);
+        self.diagnostic_level = !self.diagnostic_level;
+        self.sleeping = false;
+        Ok(())
+    }
+
+    /// Blanks the display (DISPOFF), GRAM content is kept
+    pub fn display_off<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<(), Error<D::Error>> {
+        if self.power_state() == PowerState::Uninitialized {
+            return Err(Error::PowerState);
+        }
+        write_short(dsi, regs::OTM8009A_CMD_DISPOFF, 0)?;
+        self.display_on = false;
+        Ok(())
+    }
+
+    /// Shows the GRAM content again (DISPON)
+    pub fn display_on<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<(), Error<D::Error>> {
+        if self.power_state() == PowerState::Uninitialized {
+            return Err(Error::PowerState);
+        }
+        write_short(dsi, regs::OTM8009A_CMD_DISPON, 0)?;
+        self.display_on = true;
+        Ok(())
+    }
+
+    /// Returns `true` in idle (8-colour) mode
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    /// Enters idle mode (IDMON): 8 colours at the idle frame rate
+    pub fn enter_idle_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<(), Error<D::Error>> {
+        if self.power_state() == PowerState::Uninitialized {
+            return Err(Error::PowerState);
+        }
+        write_short(dsi, regs::OTM8009A_CMD_IDMON, 0)?;
+        self.idle = true;
+        Ok(())
+    }
+
+    /// Leaves idle mode (IDMOFF)
+    pub fn exit_idle_mode<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<(), Error<D::Error>> {
+        if self.power_state() == PowerState::Uninitialized {
+            return Err(Error::PowerState);
+        }
+        write_short(dsi, regs::OTM8009A_CMD_IDMOFF, 0)?;
+        self.idle = false;
+        Ok(())
+    }
+
+    /// Returns `true` in partial display mode
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// Sets the rows kept lit in partial mode (PLTAR), both ends inclusive
+    ///
+    /// Rows are counted in the configured orientation, from 0 to `rows - 1`,
+    /// and mapped to the physical panel lines, so a strip at the top stays at
+    /// the top with a 180 degree rotation or a vertical flip. PLTAR can only
+    /// select panel lines, which the configured rows cross when rotated by 90
+    /// or 270 degrees, so [`ConfigError::OrientationMismatch`] is returned
+    /// then; [`set_partial_lines`](Self::set_partial_lines) still works.
+    pub fn set_partial_area<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        start_row: u16,
+        end_row: u16,
+    ) -> Result<(), Error<D::Error>> {
+        let config = self.config.ok_or(Error::PowerState)?;
+        if start_row > end_row || end_row >= config.rows {
+            return Err(Error::OutOfBounds);
+        }
+        if config.orientation.swaps_axes() {
+            return Err(Error::InvalidConfig(ConfigError::OrientationMismatch));
+        }
+        if config.madctr() & regs::OTM8009A_MADCTR_MY != 0 {
+            self.set_partial_lines(dsi, GRAM_ROWS - 1 - end_row, GRAM_ROWS - 1 - start_row)
+        } else {
+            self.set_partial_lines(dsi, start_row, end_row)
+        }
+    }
+
+    /// Sets the physical panel lines kept lit in partial mode (PLTAR), both
+    /// ends inclusive
+    ///
+    /// Lines run from 0 to 799 along the long side of the glass, whatever the
+    /// configured orientation.
+    pub fn set_partial_lines<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        start_line: u16,
+        end_line: u16,
+    ) -> Result<(), Error<D::Error>> {
+        if self.power_state() == PowerState::Uninitialized {
+            return Err(Error::PowerState);
+        }
+        if start_line > end_line || end_line >= GRAM_ROWS {
+            return Err(Error::OutOfBounds);
+        }
+        let [sl_hi, sl_lo] = start_line.to_be_bytes();
+        let [el_hi, el_lo] = end_line.to_be_bytes();
+        dsi.write(DsiWriteCommand::DcsLongWrite {
+            arg: regs::OTM8009A_CMD_PLTAR,
+            data: &[sl_hi, sl_lo, el_hi, el_lo],
+        })?;
+        Ok(())
+    }
+
+    /// Enters partial mode (PTLON): only the partial area is displayed
+    pub fn enter_partial_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<(), Error<D::Error>> {
+        if self.power_state() == PowerState::Uninitialized {
+            return Err(Error::PowerState);
+        }
+        write_short(dsi, regs::OTM8009A_CMD_PTLON, 0)?;
+        self.partial = true;
+        Ok(())
+    }
+
+    /// Leaves partial mode (NORON)
+    pub fn enter_normal_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<(), Error<D::Error>> {
+        if self.power_state() == PowerState::Uninitialized {
+            return Err(Error::PowerState);
+        }
+        write_short(dsi, regs::OTM8009A_CMD_NORON, 0)?;
+        self.partial = false;
+        Ok(())
+    }
+}
@@ -3,11 +3,19 @@
 // Version of 14 June 2012
 pub const OTM8009A_CMD_NOP: u8 = 0x00; // NOP command
 pub const OTM8009A_CMD_SWRESET: u8 = 0x01; // Sw reset command
+pub const OTM8009A_CMD_RDDID: u8 = 0x04; // Read display identification information (ID1, ID2, ID3)
+pub const OTM8009A_CMD_RDNUMED: u8 = 0x05; // Read number of errors on DSI
+pub const OTM8009A_CMD_RDDST: u8 = 0x09; // Read display status (4 parameters)
+pub const OTM8009A_CMD_RDDPM: u8 = 0x0A; // Read display power mode
 pub const OTM8009A_CMD_RDDMADCTL: u8 = 0x0B; // Read Display MADCTR command : read memory display access ctrl
 pub const OTM8009A_CMD_RDDCOLMOD: u8 = 0x0C; // Read Display pixel format
+pub const OTM8009A_CMD_RDDIM: u8 = 0x0D; // Read display image mode
+pub const OTM8009A_CMD_RDDSM: u8 = 0x0E; // Read display signal mode
+pub const OTM8009A_CMD_RDDSDR: u8 = 0x0F; // Read display self-diagnostic result
 pub const OTM8009A_CMD_SLPIN: u8 = 0x10; // Sleep In command
 pub const OTM8009A_CMD_SLPOUT: u8 = 0x11; // Sleep Out command
 pub const OTM8009A_CMD_PTLON: u8 = 0x12; // Partial mode On command
+pub const OTM8009A_CMD_NORON: u8 = 0x13; // Normal display mode On command
 
 pub const OTM8009A_CMD_DISPOFF: u8 = 0x28; // Display Off command
 pub const OTM8009A_CMD_DISPON: u8 = 0x29; // Display On command
@@ -34,6 +42,14 @@ pub const OTM8009A_CMD_MADCTR: u8 = 0x36; // Memory Access write control command
 pub const OTM8009A_MADCTR_MODE_PORTRAIT: u8 = 0x00;
 pub const OTM8009A_MADCTR_MODE_LANDSCAPE: u8 = 0x60; // MY = 0, MX = 1, MV = 1, ML = 0, RGB = 0
 
+// MADCTR bits
+pub const OTM8009A_MADCTR_MY: u8 = 0x80; // Row address order
+pub const OTM8009A_MADCTR_MX: u8 = 0x40; // Column address order
+pub const OTM8009A_MADCTR_MV: u8 = 0x20; // Row / column exchange
+pub const OTM8009A_MADCTR_ML: u8 = 0x10; // Vertical refresh order
+pub const OTM8009A_MADCTR_BGR: u8 = 0x08; // RGB / BGR order
+pub const OTM8009A_MADCTR_MH: u8 = 0x04; // Horizontal refresh order (RDDMADCTL / RDDST only)
+
 pub const OTM8009A_CMD_IDMOFF: u8 = 0x38; // Idle mode Off command
 pub const OTM8009A_CMD_IDMON: u8 = 0x39; // Idle mode On command
 
@@ -41,6 +57,7 @@ pub const OTM8009A_CMD_COLMOD: u8 = 0x3A; // Interface Pixel format command
 
 // Possible values of COLMOD parameter corresponding to used pixel formats
 pub const OTM8009A_COLMOD_RGB565: u8 = 0x55;
+pub const OTM8009A_COLMOD_RGB666: u8 = 0x66;
 pub const OTM8009A_COLMOD_RGB888: u8 = 0x77;
 pub const OTM8009A_COLMOD_RGB888_3T: u8 = 0b1110_0111;
 
@@ -52,10 +69,23 @@ pub const OTM8009A_CMD_RDSCNL: u8 = 0x45; // Read  Tearing Effect Scan line comm
 
 // CABC Management : ie : Content Adaptive Back light Control in IC OTM8009a
 pub const OTM8009A_CMD_WRDISBV: u8 = 0x51; // Write Display Brightness command
+pub const OTM8009A_CMD_RDDISBV: u8 = 0x52; // Read Display Brightness command
 pub const OTM8009A_CMD_WRCTRLD: u8 = 0x53; // Write CTRL Display command
+pub const OTM8009A_CMD_RDCTRLD: u8 = 0x54; // Read CTRL Display command
 pub const OTM8009A_CMD_WRCABC: u8 = 0x55; // Write Content Adaptive Brightness command
+pub const OTM8009A_CMD_RDCABC: u8 = 0x56; // Read Content Adaptive Brightness command
 pub const OTM8009A_CMD_WRCABCMB: u8 = 0x5E; // Write CABC Minimum Brightness command
+pub const OTM8009A_CMD_RDCABCMB: u8 = 0x5F; // Read CABC Minimum Brightness command
+
+// WRCTRLD bits
+pub const OTM8009A_CTRLD_BCTRL: u8 = 0x20; // Brightness control block on
+pub const OTM8009A_CTRLD_DD: u8 = 0x08; // Display dimming on
+pub const OTM8009A_CTRLD_BL: u8 = 0x04; // Backlight on
+
+pub const OTM8009A_CMD_RDDDB: u8 = 0xA1; // Read DDB start (supplier ID and supplier elective data)
 
 pub const OTM8009A_CMD_ID1: u8 = 0xDA; // Read ID1 command
 pub const OTM8009A_CMD_ID2: u8 = 0xDB; // Read ID2 command
 pub const OTM8009A_CMD_ID3: u8 = 0xDC; // Read ID3 command
+
+pub const OTM8009A_CMD_CMD2ENA: u8 = 0xFF; // Command 2 enable (vendor command set access key)
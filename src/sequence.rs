@@ -0,0 +1,244 @@
+//! Table-driven vendor (CMD2) initialisation sequence
+//!
+//! The vendor specific part of the OTM8009A bring-up is a long list of
+//! register writes in the CMD2 address space. Every CMD2 register is reached by
+//! first writing its low address byte with a DCS NOP (address shift) and then
+//! writing the high byte as a DCS command. The table below lists those steps
+//! one by one so it can be reviewed against the datasheet, and a different
+//! table can be passed to [`Otm8009A::init_with_sequence`](crate::Otm8009A::init_with_sequence).
+
+use crate::{regs, Otm8009AConfig};
+
+/// Configuration dependent register value
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConfigParam {
+    /// Oscillator adjustment (0xC181), idle mode frame rate in the high
+    /// nibble and normal mode frame rate in the low nibble
+    FrameRate,
+    /// VCOMDC (0xD900) code of `vcom`
+    Vcom,
+    /// GVDD (0xD800) code of `gvdd`
+    Gvdd,
+    /// NGVDD (0xD801) code of `ngvdd`
+    Ngvdd,
+}
+
+impl ConfigParam {
+    pub(crate) fn value(self, config: &Otm8009AConfig) -> u8 {
+        match self {
+            ConfigParam::FrameRate => {
+                let idle = config.idle_frame_rate.unwrap_or(config.frame_rate) as u8;
+                (idle << 4) | config.frame_rate as u8
+            }
+            ConfigParam::Vcom => config.vcom_code(),
+            ConfigParam::Gvdd => config.gvdd_code(),
+            ConfigParam::Ngvdd => config.ngvdd_code(),
+        }
+    }
+}
+
+/// Largest number of parameters of an [`InitStep::ConfigLong`]
+pub const CONFIG_LONG_MAX: usize = 16;
+
+/// One step of an initialisation sequence
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InitStep {
+    /// Address shift: DCS NOP carrying the low byte of the next register address
+    Shift(u8),
+    /// DCS short write with one parameter
+    Short(u8, u8),
+    /// DCS long write
+    Long(u8, &'static [u8]),
+    /// DCS short write with a parameter taken from the configuration
+    Config(u8, ConfigParam),
+    /// DCS long write with parameters taken from the configuration, at most
+    /// [`CONFIG_LONG_MAX`] of them
+    ConfigLong(u8, &'static [ConfigParam]),
+    /// Delay in milliseconds
+    Delay(u16),
+}
+
+use InitStep::*;
+
+/// Vendor part of the reference initialisation, from entering CMD2 to leaving it
+pub const DEFAULT_SEQUENCE: &[InitStep] = &[
+    // Enable CMD2 to access vendor specific commands
+    // Enter in command 2 mode and set EXTC to enable address shift function (0x00)
+    Shift(0x00),
+    Long(regs::OTM8009A_CMD_CMD2ENA, &[0x80, 0x09, 0x01]),
+    Shift(0x80),
+    Long(regs::OTM8009A_CMD_CMD2ENA, &[0x80, 0x09]),
+    // SD_PCH_CTRL - 0xC480h - 129th parameter - Default 0x00
+    // Set SD_PT
+    // Source output level during porch and non-display area to GND
+    Shift(0x80),
+    Short(0xc4, 0x30),
+    Delay(10),
+    Shift(0x8a),
+    Short(0xc4, 0x40),
+    Delay(10),
+    // PWR_CTRL4 - 0xC4B0h - 178th parameter - Default 0xA8
+    // Set gvdd_en_test
+    // -> enable GVDD test mode !!!
+    Shift(0xb1),
+    Short(0xc5, 0xa9),
+    // PWR_CTRL2 - 0xC590h - 146th parameter - Default 0x79
+    // Set pump 4 vgh voltage
+    // -> from 15.0v down to 13.0v
+    // Set pump 5 vgh voltage
+    // -> from -12.0v downto -9.0v
+    Shift(0x91),
+    Short(0xc5, 0x34),
+    // P_DRV_M - 0xC0B4h - 181th parameter - Default 0x00
+    // -> Column inversion
+    Shift(0xb4),
+    Short(0xc0, 0x50),
+    // VCOMDC - 0xD900h - 1st parameter - Default 0x39h
+    // VCOM Voltage settings
+    // -> from -1.0000v downto -1.2625v by default
+    Shift(0x00),
+    Config(0xd9, ConfigParam::Vcom),
+    // Oscillator adjustment for Idle/Normal mode (LPDT only)
+    Shift(0x81),
+    Config(0xc1, ConfigParam::FrameRate),
+    // Video mode internal
+    Shift(0xa1),
+    Short(0xc1, 0x08),
+    // PWR_CTRL2 - 0xC590h - 147h parameter - Default 0x00
+    // Set pump 4&5 x6
+    // -> ONLY VALID when PUMP4_EN_ASDM_HV = "0"
+    Shift(0x92),
+    Short(0xc5, 0x01),
+    // PWR_CTRL2 - 0xC590h - 150th parameter - Default 0x33h
+    // Change pump4 clock ratio
+    // -> from 1 line to 1/2 line
+    Shift(0x95),
+    Short(0xc5, 0x34),
+    // GVDD/NGVDD settings
+    // -> 4.8125v / -4.8125v by default
+    Shift(0x00),
+    ConfigLong(0xd8, &[ConfigParam::Gvdd, ConfigParam::Ngvdd]),
+    // PWR_CTRL2 - 0xC590h - 149th parameter - Default 0x33h
+    // Rewrite the default value !
+    Shift(0x94),
+    Short(0xc5, 0x33),
+    // Panel display timing Setting 3
+    Shift(0xa3),
+    Short(0xc0, 0x1b),
+    // Power control 1
+    Shift(0x82),
+    Short(0xc5, 0x83),
+    // Source driver precharge
+    Shift(0x81),
+    Short(0xc4, 0x83),
+    Shift(0xa1),
+    Short(0xc1, 0x0e),
+    Shift(0xa6),
+    Long(0xb3, &[0x00, 0x01]),
+    // GOAVST
+    Shift(0x80),
+    Long(0xce, &[0x85, 0x01, 0x00, 0x84, 0x01, 0x00]),
+    Shift(0xa0),
+    Long(
+        0xce,
+        &[
+            0x18, 0x04, 0x03, 0x39, 0x00, 0x00, 0x00, 0x18, 0x03, 0x03, 0x3A, 0x00, 0x00, 0x00,
+        ],
+    ),
+    Shift(0xb0),
+    Long(
+        0xce,
+        &[
+            0x18, 0x02, 0x03, 0x3B, 0x00, 0x00, 0x00, 0x18, 0x01, 0x03, 0x3C, 0x00, 0x00, 0x00,
+        ],
+    ),
+    Shift(0xc0),
+    Long(
+        0xcf,
+        &[0x01, 0x01, 0x20, 0x20, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00],
+    ),
+    Shift(0xd0),
+    Short(0xcf, 0x00),
+    Shift(0x80),
+    Long(0xcb, &[0u8; 10]),
+    Shift(0x90),
+    Long(0xcb, &[0u8; 15]),
+    Shift(0xa0),
+    Long(0xcb, &[0u8; 15]),
+    Shift(0xb0),
+    Long(0xcb, &[0u8; 10]),
+    Shift(0xc0),
+    Long(
+        0xcb,
+        &[
+            0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00,
+        ],
+    ),
+    Shift(0xd0),
+    Long(
+        0xcb,
+        &[
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00,
+            0x00,
+        ],
+    ),
+    Shift(0xe0),
+    Long(0xcb, &[0u8; 10]),
+    Shift(0xf0),
+    Long(0xcb, &[255u8; 10]),
+    Shift(0x80),
+    Long(
+        0xcc,
+        &[0x00, 0x26, 0x09, 0x0B, 0x01, 0x25, 0x00, 0x00, 0x00, 0x00],
+    ),
+    Shift(0x90),
+    Long(
+        0xcc,
+        &[
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x26, 0x0A, 0x0C,
+            0x02,
+        ],
+    ),
+    Shift(0xa0),
+    Long(
+        0xcc,
+        &[
+            0x25, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00,
+        ],
+    ),
+    Shift(0xb0),
+    Long(
+        0xcc,
+        &[0x00, 0x25, 0x0C, 0x0A, 0x02, 0x26, 0x00, 0x00, 0x00, 0x00],
+    ),
+    Shift(0xc0),
+    Long(
+        0xcc,
+        &[
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x25, 0x0B, 0x09,
+            0x01,
+        ],
+    ),
+    Shift(0xd0),
+    Long(
+        0xcc,
+        &[
+            0x26, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00,
+        ],
+    ),
+    // PWR_CTRL1 - 0xc580h - 130th parameter - default
+    // Pump 1 min and max DM
+    Shift(0x81),
+    Short(0xc5, 0x66),
+    Shift(0xb6),
+    Short(0xf5, 0x06),
+    // CABC LEDPWM frequency adjusted to 19,5kHz
+    Shift(0xb1),
+    Short(0xc6, 0x06),
+    // Exit CMD2 mode
+    Shift(0x00),
+    Long(regs::OTM8009A_CMD_CMD2ENA, &[0xff, 0xff, 0xff]),
+];
@@ -0,0 +1,288 @@
+//! Standard DCS status and diagnostic registers
+
+use crate::otm8009a::{read_byte, read_dcs};
+use crate::{regs, Error, Otm8009A, PixelFormat};
+use embedded_display_controller::dsi::DsiHostCtrlIo;
+
+const fn bit(bits: u8, n: u8) -> bool {
+    bits & (1 << n) != 0
+}
+
+/// Memory access control as read back by RDDMADCTL (and RDDST)
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AddressMode {
+    /// Row address order (MY)
+    pub row_order: bool,
+    /// Column address order (MX)
+    pub column_order: bool,
+    /// Row / column exchange (MV)
+    pub row_column_exchange: bool,
+    /// Vertical refresh order (ML)
+    pub vertical_refresh_order: bool,
+    /// BGR colour order
+    pub bgr: bool,
+    /// Horizontal refresh order (MH)
+    pub horizontal_refresh_order: bool,
+}
+
+impl AddressMode {
+    pub const fn bits(&self) -> u8 {
+        let mut bits = 0;
+        if self.row_order {
+            bits |= regs::OTM8009A_MADCTR_MY;
+        }
+        if self.column_order {
+            bits |= regs::OTM8009A_MADCTR_MX;
+        }
+        if self.row_column_exchange {
+            bits |= regs::OTM8009A_MADCTR_MV;
+        }
+        if self.vertical_refresh_order {
+            bits |= regs::OTM8009A_MADCTR_ML;
+        }
+        if self.bgr {
+            bits |= regs::OTM8009A_MADCTR_BGR;
+        }
+        if self.horizontal_refresh_order {
+            bits |= regs::OTM8009A_MADCTR_MH;
+        }
+        bits
+    }
+
+    pub const fn from_bits(bits: u8) -> Self {
+        AddressMode {
+            row_order: bits & regs::OTM8009A_MADCTR_MY != 0,
+            column_order: bits & regs::OTM8009A_MADCTR_MX != 0,
+            row_column_exchange: bits & regs::OTM8009A_MADCTR_MV != 0,
+            vertical_refresh_order: bits & regs::OTM8009A_MADCTR_ML != 0,
+            bgr: bits & regs::OTM8009A_MADCTR_BGR != 0,
+            horizontal_refresh_order: bits & regs::OTM8009A_MADCTR_MH != 0,
+        }
+    }
+}
+
+/// RDDPM bits
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PowerMode {
+    pub booster_on: bool,
+    pub idle_mode: bool,
+    pub partial_mode: bool,
+    pub sleep_out: bool,
+    pub normal_mode: bool,
+    pub display_on: bool,
+}
+
+impl PowerMode {
+    pub const fn from_bits(bits: u8) -> Self {
+        PowerMode {
+            booster_on: bit(bits, 7),
+            idle_mode: bit(bits, 6),
+            partial_mode: bit(bits, 5),
+            sleep_out: bit(bits, 4),
+            normal_mode: bit(bits, 3),
+            display_on: bit(bits, 2),
+        }
+    }
+}
+
+/// RDDIM bits
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ImageMode {
+    pub vertical_scrolling: bool,
+    pub inversion: bool,
+    pub all_pixels_on: bool,
+    pub all_pixels_off: bool,
+    /// Gamma curve selection (GCS2..GCS0)
+    pub gamma_curve: u8,
+}
+
+impl ImageMode {
+    pub const fn from_bits(bits: u8) -> Self {
+        ImageMode {
+            vertical_scrolling: bit(bits, 7),
+            inversion: bit(bits, 5),
+            all_pixels_on: bit(bits, 4),
+            all_pixels_off: bit(bits, 3),
+            gamma_curve: bits & 0b111,
+        }
+    }
+}
+
+/// RDDSM bits
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SignalMode {
+    /// Tearing effect line output enabled
+    pub tearing_effect: bool,
+    /// Tearing effect line carries H-blanking as well as V-blanking
+    pub tearing_effect_hblank: bool,
+}
+
+impl SignalMode {
+    pub const fn from_bits(bits: u8) -> Self {
+        SignalMode {
+            tearing_effect: bit(bits, 7),
+            tearing_effect_hblank: bit(bits, 6),
+        }
+    }
+}
+
+/// RDDSDR bits
+///
+/// The register loading and functionality bits are inverted by the panel
+/// after sleep out when the corresponding check passed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SelfDiagnostic {
+    /// Register loading detection (RELD)
+    pub register_loading: bool,
+    /// Functionality detection (FUND)
+    pub functionality: bool,
+    /// Chip attachment detection (ATTD)
+    pub chip_attachment: bool,
+    /// Display glass break detection (BRD)
+    pub glass_break: bool,
+}
+
+impl SelfDiagnostic {
+    pub const fn from_bits(bits: u8) -> Self {
+        SelfDiagnostic {
+            register_loading: bit(bits, 7),
+            functionality: bit(bits, 6),
+            chip_attachment: bit(bits, 5),
+            glass_break: bit(bits, 4),
+        }
+    }
+}
+
+/// RDNUMED value
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DsiErrorCount {
+    /// Number of DSI errors since the last read
+    pub count: u8,
+    /// The counter overflowed
+    pub overflow: bool,
+}
+
+impl DsiErrorCount {
+    pub const fn from_bits(bits: u8) -> Self {
+        DsiErrorCount {
+            count: bits & 0x7f,
+            overflow: bit(bits, 7),
+        }
+    }
+}
+
+/// RDDST parameters
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DisplayStatus {
+    pub booster_on: bool,
+    pub address_mode: AddressMode,
+    /// Interface colour pixel format (IFPF2..IFPF0)
+    pub interface_pixel_format: u8,
+    pub idle_mode: bool,
+    pub partial_mode: bool,
+    pub sleep_out: bool,
+    pub normal_mode: bool,
+    pub vertical_scrolling: bool,
+    pub inversion: bool,
+    pub all_pixels_on: bool,
+    pub all_pixels_off: bool,
+    pub display_on: bool,
+    pub tearing_effect: bool,
+    /// Gamma curve selection (GCS2..GCS0)
+    pub gamma_curve: u8,
+    /// Tearing effect line carries H-blanking as well as V-blanking
+    pub tearing_effect_hblank: bool,
+}
+
+impl DisplayStatus {
+    pub const fn from_bytes(bytes: [u8; 4]) -> Self {
+        DisplayStatus {
+            booster_on: bit(bytes[0], 7),
+            address_mode: AddressMode::from_bits(bytes[0] << 1),
+            interface_pixel_format: (bytes[1] >> 4) & 0b111,
+            idle_mode: bit(bytes[1], 3),
+            partial_mode: bit(bytes[1], 2),
+            sleep_out: bit(bytes[1], 1),
+            normal_mode: bit(bytes[1], 0),
+            vertical_scrolling: bit(bytes[2], 7),
+            inversion: bit(bytes[2], 5),
+            all_pixels_on: bit(bytes[2], 4),
+            all_pixels_off: bit(bytes[2], 3),
+            display_on: bit(bytes[2], 2),
+            tearing_effect: bit(bytes[2], 1),
+            gamma_curve: ((bytes[2] & 1) << 2) | (bytes[3] >> 6),
+            tearing_effect_hblank: bit(bytes[3], 5),
+        }
+    }
+}
+
+impl Otm8009A {
+    /// Reads the display status (RDDST)
+    pub fn display_status<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<DisplayStatus, Error<D::Error>> {
+        let mut buf = [0u8; 4];
+        read_dcs(dsi, regs::OTM8009A_CMD_RDDST, &mut buf)?;
+        Ok(DisplayStatus::from_bytes(buf))
+    }
+
+    /// Reads the display power mode (RDDPM)
+    pub fn power_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<PowerMode, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDDPM).map(PowerMode::from_bits)
+    }
+
+    /// Reads the memory access control back (RDDMADCTL)
+    pub fn address_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<AddressMode, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDDMADCTL).map(AddressMode::from_bits)
+    }
+
+    /// Reads the interface pixel format back (RDDCOLMOD), `None` if the value
+    /// is not one of [`PixelFormat`]
+    pub fn read_pixel_format<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<Option<PixelFormat>, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDDCOLMOD).map(PixelFormat::from_bits)
+    }
+
+    /// Reads the display image mode (RDDIM)
+    pub fn image_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<ImageMode, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDDIM).map(ImageMode::from_bits)
+    }
+
+    /// Reads the display signal mode (RDDSM)
+    pub fn signal_mode<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<SignalMode, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDDSM).map(SignalMode::from_bits)
+    }
+
+    /// Reads the self-diagnostic result (RDDSDR)
+    pub fn self_diagnostic<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<SelfDiagnostic, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDDSDR).map(SelfDiagnostic::from_bits)
+    }
+
+    /// Reads the number of errors on the DSI link (RDNUMED)
+    ///
+    /// The panel clears the counter when it is read.
+    pub fn dsi_error_count<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<DsiErrorCount, Error<D::Error>> {
+        read_byte(dsi, regs::OTM8009A_CMD_RDNUMED).map(DsiErrorCount::from_bits)
+    }
+}
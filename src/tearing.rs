@@ -0,0 +1,59 @@
+//! Tearing effect (TE) output and scanline readback
+
+use crate::otm8009a::{read_dcs, write_short};
+use crate::{regs, Error, Otm8009A, GRAM_ROWS};
+use embedded_display_controller::dsi::{DsiHostCtrlIo, DsiWriteCommand};
+
+/// Information carried by the TE line (TEEON TELOM parameter)
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TearingEffectMode {
+    /// V-blanking only
+    VBlank = regs::OTM8009A_TEEON_TELOM_VBLANKING_INFO_ONLY,
+    /// V-blanking and H-blanking
+    VBlankHBlank = regs::OTM8009A_TEEON_TELOM_VBLANKING_AND_HBLANKING_INFO,
+}
+
+impl Otm8009A {
+    /// Turns the TE output on (TEEON)
+    pub fn enable_tearing_effect<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        mode: TearingEffectMode,
+    ) -> Result<(), Error<D::Error>> {
+        write_short(dsi, regs::OTM8009A_CMD_TEEON, mode as u8)
+    }
+
+    /// Turns the TE output off (TEOFF)
+    pub fn disable_tearing_effect<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+    ) -> Result<(), Error<D::Error>> {
+        write_short(dsi, regs::OTM8009A_CMD_TEOFF, 0)
+    }
+
+    /// Sets the scanline the TE output is raised on (WRTESCN)
+    ///
+    /// `line` counts panel lines, `0..800` whatever the orientation.
+    pub fn set_tearing_effect_scanline<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        line: u16,
+    ) -> Result<(), Error<D::Error>> {
+        if line >= GRAM_ROWS {
+            return Err(Error::OutOfBounds);
+        }
+        dsi.write(DsiWriteCommand::DcsLongWrite {
+            arg: regs::OTM8009A_CMD_WRTESCN,
+            data: &line.to_be_bytes(),
+        })?;
+        Ok(())
+    }
+
+    /// Reads the scanline currently being refreshed (RDSCNL)
+    pub fn scanline<D: DsiHostCtrlIo>(&mut self, dsi: &mut D) -> Result<u16, Error<D::Error>> {
+        let mut buf = [0u8; 2];
+        read_dcs(dsi, regs::OTM8009A_CMD_RDSCNL, &mut buf)?;
+        Ok(u16::from_be_bytes(buf))
+    }
+}
@@ -0,0 +1,255 @@
+//! Power state machine checked at compile time
+//!
+//! [`Panel`] wraps an [`Otm8009A`] and encodes its power state in the type, so
+//! that for example `sleep` can only be called on an awake panel. Transitions
+//! consume the panel and return it in its next state. If a transition fails the
+//! panel is handed back in its previous state together with the error, so the
+//! caller can retry or fall back to [`Panel::into_inner`].
+//!
+//! Operations that leave the power state alone are forwarded by the awake
+//! states. `recover`, the raw CMD2 register access and the driver's own sleep
+//! and display on/off calls are not, they are only reachable through
+//! [`Panel::into_inner`].
+//!
+//! ```text
+//! Uninit --init--> DisplayOn --display_off--> Awake --sleep--> Sleeping
+//!                  DisplayOn <--display_on--  Awake <--wake--  Sleeping
+//! ```
+//!
+//! The panel ID can be read in every state, so it can be checked before
+//! `init`. Wrong call orders do not compile:
+//!
+//! ```
+//! # use otm8009a::mock::MockDsi;
+//! # use otm8009a::typestate::{DisplayOn, Panel};
+//! fn blank(panel: Panel<DisplayOn>, dsi: &MockDsi) {
+//!     if let Ok(mut panel) = panel.display_off(&mut dsi.clone()) {
+//!         let _ = panel.write_pixels(&mut dsi.clone(), &[0; 3], 64);
+//!         let _ = panel.sleep(&mut dsi.clone(), &mut dsi.delay());
+//!     }
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use otm8009a::mock::MockDsi;
+//! # use otm8009a::typestate::{DisplayOn, Panel};
+//! fn blank(panel: Panel<DisplayOn>, dsi: &MockDsi) {
+//!     // The display has to be switched off first
+//!     let _ = panel.sleep(&mut dsi.clone(), &mut dsi.delay());
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! # use otm8009a::mock::MockDsi;
+//! # use otm8009a::typestate::{Panel, Sleeping};
+//! fn draw(mut panel: Panel<Sleeping>, dsi: &MockDsi) {
+//!     // GRAM is not accessible in sleep mode
+//!     let _ = panel.write_pixels(&mut dsi.clone(), &[0; 3], 64);
+//! }
+//! ```
+
+// A failed transition hands back the whole driver, there is no heap to box it
+#![allow(clippy::result_large_err)]
+
+use crate::{
+    AddressMode, CabcMode, ColorMap, DigitalGamma, DisplayControl, DisplayStatus, DsiErrorCount,
+    Error, Gamma, HealthFault, ImageMode, MemoryReport, Millivolts, Orientation, Otm8009A,
+    Otm8009AConfig, PanelId, PixelFormat, PowerMode, SelfDiagnostic, SignalMode, TearingEffectMode,
+    Window,
+};
+use core::marker::PhantomData;
+use embedded_display_controller::dsi::DsiHostCtrlIo;
+use embedded_hal::blocking::delay::DelayMs;
+
+/// `init` has not been called yet
+#[derive(Debug)]
+pub struct Uninit;
+/// Sleep mode with the display off
+#[derive(Debug)]
+pub struct Sleeping;
+/// Out of sleep with the display off
+#[derive(Debug)]
+pub struct Awake;
+/// Out of sleep with the display on
+#[derive(Debug)]
+pub struct DisplayOn;
+
+mod sealed {
+    /// Out of sleep, the panel accepts every command
+    pub trait Active {}
+
+    impl Active for super::Awake {}
+    impl Active for super::DisplayOn {}
+}
+
+/// Forwards driver methods that take the DSI host as only non-`self` borrow
+macro_rules! forward {
+    ($(fn $name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("See [`Otm8009A::", stringify!($name), "`]")]
+            pub fn $name<D: DsiHostCtrlIo>(
+                &mut self,
+                dsi: &mut D,
+                $($arg: $ty),*
+            ) -> Result<$ret, Error<D::Error>> {
+                self.driver.$name(dsi, $($arg),*)
+            }
+        )*
+    };
+}
+
+#[derive(Debug)]
+pub struct Panel<S> {
+    driver: Otm8009A,
+    _state: PhantomData<S>,
+}
+
+impl<S> Panel<S> {
+    fn transition<T, E>(self, result: Result<(), Error<E>>) -> Result<Panel<T>, (Self, Error<E>)> {
+        match result {
+            Ok(()) => Ok(Panel {
+                driver: self.driver,
+                _state: PhantomData,
+            }),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    pub fn driver(&self) -> &Otm8009A {
+        &self.driver
+    }
+
+    /// See [`Otm8009A::config`]
+    pub fn config(&self) -> Option<&Otm8009AConfig> {
+        self.driver.config()
+    }
+
+    forward! {
+        fn id_matches() -> ();
+        fn read_id() -> PanelId;
+    }
+
+    /// Gives up the compile time checks and returns the wrapped driver
+    pub fn into_inner(self) -> Otm8009A {
+        self.driver
+    }
+}
+
+impl Default for Panel<Uninit> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Panel<Uninit> {
+    pub fn new() -> Self {
+        Panel {
+            driver: Otm8009A::new(),
+            _state: PhantomData,
+        }
+    }
+
+    pub fn init<D: DsiHostCtrlIo>(
+        mut self,
+        dsi: &mut D,
+        config: Otm8009AConfig,
+        delay: &mut impl DelayMs<u32>,
+    ) -> Result<Panel<DisplayOn>, (Self, Error<D::Error>)> {
+        let result = self.driver.init(dsi, config, delay);
+        self.transition(result)
+    }
+}
+
+impl Panel<DisplayOn> {
+    pub fn display_off<D: DsiHostCtrlIo>(
+        mut self,
+        dsi: &mut D,
+    ) -> Result<Panel<Awake>, (Self, Error<D::Error>)> {
+        let result = self.driver.display_off(dsi);
+        self.transition(result)
+    }
+}
+
+impl Panel<Awake> {
+    pub fn display_on<D: DsiHostCtrlIo>(
+        mut self,
+        dsi: &mut D,
+    ) -> Result<Panel<DisplayOn>, (Self, Error<D::Error>)> {
+        let result = self.driver.display_on(dsi);
+        self.transition(result)
+    }
+
+    pub fn sleep<D: DsiHostCtrlIo>(
+        mut self,
+        dsi: &mut D,
+        delay: &mut impl DelayMs<u32>,
+    ) -> Result<Panel<Sleeping>, (Self, Error<D::Error>)> {
+        let result = self.driver.sleep(dsi, delay);
+        self.transition(result)
+    }
+}
+
+impl Panel<Sleeping> {
+    pub fn wake<D: DsiHostCtrlIo>(
+        mut self,
+        dsi: &mut D,
+        delay: &mut impl DelayMs<u32>,
+    ) -> Result<Panel<Awake>, (Self, Error<D::Error>)> {
+        let result = self.driver.wake(dsi, delay);
+        self.transition(result)
+    }
+}
+
+impl<S: sealed::Active> Panel<S> {
+    /// See [`Otm8009A::enable_te_output`]
+    pub fn enable_te_output<D: DsiHostCtrlIo>(
+        &mut self,
+        on_line: u16,
+        dsi: &mut D,
+    ) -> Result<(), Error<D::Error>> {
+        self.driver.enable_te_output(on_line, dsi)
+    }
+
+    forward! {
+        fn set_pixel_format(pixel_format: PixelFormat) -> ();
+        fn set_orientation(orientation: Orientation) -> ();
+        fn set_color_map(color_map: ColorMap) -> ();
+        fn set_brightness(brightness: u8) -> ();
+        fn brightness() -> u8;
+        fn set_display_control(control: DisplayControl) -> ();
+        fn display_control() -> DisplayControl;
+        fn set_cabc_mode(mode: CabcMode) -> ();
+        fn cabc_mode() -> CabcMode;
+        fn set_cabc_min_brightness(brightness: u8) -> ();
+        fn cabc_min_brightness() -> u8;
+        fn enter_idle_mode() -> ();
+        fn exit_idle_mode() -> ();
+        fn set_partial_area(start_row: u16, end_row: u16) -> ();
+        fn set_partial_lines(start_line: u16, end_line: u16) -> ();
+        fn enter_partial_mode() -> ();
+        fn enter_normal_mode() -> ();
+        fn set_window(x0: u16, y0: u16, x1: u16, y1: u16) -> ();
+        fn write_pixels(pixels: &[u8], max_packet_size: u16) -> ();
+        fn read_pixels(window: Window, buf: &mut [u8], max_packet_size: u16) -> ();
+        fn memory_check(region: Window, max_packet_size: u16) -> MemoryReport;
+        fn display_status() -> DisplayStatus;
+        fn power_mode() -> PowerMode;
+        fn address_mode() -> AddressMode;
+        fn read_pixel_format() -> Option<PixelFormat>;
+        fn image_mode() -> ImageMode;
+        fn signal_mode() -> SignalMode;
+        fn self_diagnostic() -> SelfDiagnostic;
+        fn dsi_error_count() -> DsiErrorCount;
+        fn health_check() -> Option<HealthFault<D::Error>>;
+        fn enable_tearing_effect(mode: TearingEffectMode) -> ();
+        fn disable_tearing_effect() -> ();
+        fn set_tearing_effect_scanline(line: u16) -> ();
+        fn scanline() -> u16;
+        fn set_gamma(gamma: Gamma) -> ();
+        fn gamma() -> Gamma;
+        fn set_digital_gamma(digital_gamma: DigitalGamma) -> ();
+        fn digital_gamma() -> DigitalGamma;
+        fn set_vcom(vcom: Millivolts) -> ();
+        fn set_gvdd(gvdd: Millivolts, ngvdd: Millivolts) -> ();
+    }
+}
@@ -0,0 +1,102 @@
+//! VCOM (0xD900) and GVDD / NGVDD (0xD800) in physical units
+//!
+//! Both registers step by 12.5 mV. The VCOM mapping follows the reference
+//! init, where code 0x39 is -1.0000 V and 0x4E is -1.2625 V. GVDD starts at
+//! 3.3 V for code 0, NGVDD mirrors it at negative voltages.
+
+use crate::{ConfigError, Error, Otm8009A};
+use embedded_display_controller::dsi::DsiHostCtrlIo;
+
+/// VCOMDC register
+const VCOMDC: u16 = 0xd900;
+/// GVDD / NGVDD register
+const GVDD_NGVDD: u16 = 0xd800;
+
+/// Voltage in millivolts
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Millivolts(pub i32);
+
+/// VCOM of the reference init, code 0x4E (-1262.5 mV)
+pub const DEFAULT_VCOM: Millivolts = Millivolts(-1263);
+/// GVDD of the reference init, code 0x79 (4812.5 mV)
+pub const DEFAULT_GVDD: Millivolts = Millivolts(4813);
+/// NGVDD of the reference init, code 0x79 (-4812.5 mV)
+pub const DEFAULT_NGVDD: Millivolts = Millivolts(-4813);
+
+/// VCOM range, codes 0x00 to 0xFF
+pub const VCOM_RANGE: (Millivolts, Millivolts) = (Millivolts(-3475), Millivolts(-288));
+/// GVDD range, NGVDD covers the same magnitudes
+pub const GVDD_RANGE: (Millivolts, Millivolts) = (Millivolts(3300), Millivolts(6000));
+
+/// Nearest 12.5 mV step of `mv` millivolts above `offset` half millivolts
+fn steps(mv: i32, offset: i32) -> u8 {
+    ((2 * mv - offset + 12) / 25) as u8
+}
+
+fn in_range(mv: Millivolts, (min, max): (Millivolts, Millivolts)) -> bool {
+    min <= mv && mv <= max
+}
+
+impl Millivolts {
+    /// VCOMDC code, `None` outside [`VCOM_RANGE`]
+    pub fn vcom_code(self) -> Option<u8> {
+        in_range(self, VCOM_RANGE).then(|| steps(-self.0, 575))
+    }
+
+    /// GVDD code, `None` outside [`GVDD_RANGE`]
+    pub fn gvdd_code(self) -> Option<u8> {
+        in_range(self, GVDD_RANGE).then(|| steps(self.0, 6600))
+    }
+
+    /// NGVDD code, `None` outside the negated [`GVDD_RANGE`]
+    pub fn ngvdd_code(self) -> Option<u8> {
+        Millivolts(-self.0).gvdd_code()
+    }
+}
+
+impl Otm8009A {
+    /// Sets the VCOM voltage (VCOMDC), for flicker tuning
+    pub fn set_vcom<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        vcom: Millivolts,
+    ) -> Result<(), Error<D::Error>> {
+        let mut config = self.config.ok_or(Error::PowerState)?;
+        config.vcom = vcom;
+        config.validate().map_err(Error::InvalidConfig)?;
+        self.write_vendor_reg(dsi, VCOMDC, &[config.vcom_code()])?;
+        self.config = Some(config);
+        Ok(())
+    }
+
+    /// Sets the gamma reference voltages GVDD and NGVDD
+    pub fn set_gvdd<D: DsiHostCtrlIo>(
+        &mut self,
+        dsi: &mut D,
+        gvdd: Millivolts,
+        ngvdd: Millivolts,
+    ) -> Result<(), Error<D::Error>> {
+        let mut config = self.config.ok_or(Error::PowerState)?;
+        config.gvdd = gvdd;
+        config.ngvdd = ngvdd;
+        config.validate().map_err(Error::InvalidConfig)?;
+        self.write_vendor_reg(dsi, GVDD_NGVDD, &[config.gvdd_code(), config.ngvdd_code()])?;
+        self.config = Some(config);
+        Ok(())
+    }
+}
+
+/// Range checks used by [`Otm8009AConfig::validate`](crate::Otm8009AConfig::validate)
+pub(crate) fn validate(
+    vcom: Millivolts,
+    gvdd: Millivolts,
+    ngvdd: Millivolts,
+) -> Result<(), ConfigError> {
+    if vcom.vcom_code().is_none() {
+        return Err(ConfigError::VcomRange);
+    }
+    if gvdd.gvdd_code().is_none() || ngvdd.ngvdd_code().is_none() {
+        return Err(ConfigError::GvddRange);
+    }
+    Ok(())
+}
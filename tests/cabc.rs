@@ -0,0 +1,71 @@
+mod common;
+
+use common::short;
+use otm8009a::mock::{MockDsi, ReadCommand, Transaction};
+use otm8009a::{CabcMode, DisplayControl, Otm8009A};
+
+fn read(arg: u8) -> Transaction {
+    Transaction::Read {
+        command: ReadCommand::DcsShort { arg },
+        len: 1,
+    }
+}
+
+#[test]
+fn setters_write_dcs_registers() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    panel.set_brightness(&mut dsi.clone(), 0x40).unwrap();
+    panel
+        .set_display_control(
+            &mut dsi.clone(),
+            DisplayControl {
+                brightness_control: true,
+                dimming: false,
+                backlight: true,
+            },
+        )
+        .unwrap();
+    panel
+        .set_cabc_mode(&mut dsi.clone(), CabcMode::MovingImage)
+        .unwrap();
+    panel
+        .set_cabc_min_brightness(&mut dsi.clone(), 0x10)
+        .unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            short(0x51, 0x40),
+            short(0x53, 0x24),
+            short(0x55, 0x03),
+            short(0x5e, 0x10),
+        ]
+    );
+}
+
+#[test]
+fn readback_decodes_registers() {
+    let dsi = MockDsi::new();
+    for reply in [0x7f, 0x2c, 0x02, 0xff] {
+        dsi.push_reply(&[reply]);
+    }
+    let mut panel = Otm8009A::new();
+    assert_eq!(panel.brightness(&mut dsi.clone()), Ok(0x7f));
+    assert_eq!(
+        panel.display_control(&mut dsi.clone()),
+        Ok(DisplayControl {
+            brightness_control: true,
+            dimming: true,
+            backlight: true,
+        })
+    );
+    assert_eq!(
+        panel.cabc_mode(&mut dsi.clone()),
+        Ok(CabcMode::StillPicture)
+    );
+    assert_eq!(panel.cabc_min_brightness(&mut dsi.clone()), Ok(0xff));
+    assert_eq!(
+        dsi.transactions(),
+        vec![read(0x52), read(0x54), read(0x56), read(0x5f)]
+    );
+}
@@ -0,0 +1,65 @@
+#![allow(dead_code)]
+
+use otm8009a::mock::{MockDsi, Transaction, WriteCommand};
+use otm8009a::{
+    ColorMap, FrameRate, Gamma, Mode, Otm8009A, Otm8009AConfig, PixelFormat, DEFAULT_GVDD,
+    DEFAULT_NGVDD, DEFAULT_VCOM,
+};
+
+pub fn short(arg: u8, data: u8) -> Transaction {
+    Transaction::Write(WriteCommand::DcsShortP1 { arg, data })
+}
+
+pub fn long(arg: u8, data: &[u8]) -> Transaction {
+    Transaction::Write(WriteCommand::DcsLongWrite {
+        arg,
+        data: data.to_vec(),
+    })
+}
+
+/// CMD2 unlock as sent by `unlock_cmd2`
+pub fn unlock() -> Vec<Transaction> {
+    vec![
+        short(0x00, 0x00),
+        long(0xff, &[0x80, 0x09, 0x01]),
+        short(0x00, 0x80),
+        long(0xff, &[0x80, 0x09]),
+    ]
+}
+
+/// CMD2 lock as sent by `lock_cmd2`
+pub fn lock() -> Vec<Transaction> {
+    vec![short(0x00, 0x00), long(0xff, &[0xff, 0xff, 0xff])]
+}
+
+pub fn config(frame_rate: FrameRate, mode: Mode, color_map: ColorMap) -> Otm8009AConfig {
+    let (cols, rows) = match mode {
+        Mode::Portrait => (480, 800),
+        Mode::Landscape => (800, 480),
+    };
+    Otm8009AConfig {
+        frame_rate,
+        idle_frame_rate: None,
+        orientation: mode.into(),
+        color_map,
+        pixel_format: PixelFormat::Rgb888,
+        cols,
+        rows,
+        gamma: Gamma::GAMMA_2_2,
+        digital_gamma: None,
+        vcom: DEFAULT_VCOM,
+        gvdd: DEFAULT_GVDD,
+        ngvdd: DEFAULT_NGVDD,
+    }
+}
+
+/// Returns a panel initialised with `config` and a mock with an empty log
+pub fn init(config: Otm8009AConfig) -> (Otm8009A, MockDsi) {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    panel
+        .init(&mut dsi.clone(), config, &mut dsi.delay())
+        .unwrap();
+    dsi.clear();
+    (panel, dsi)
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use common::{config, init, lock, long, short, unlock};
+use otm8009a::mock::{MockDsi, ReadCommand, Transaction, WriteCommand};
+use otm8009a::{
+    ColorMap, ConfigError, DigitalGamma, Error, FrameRate, Gamma, GammaError, Mode, Otm8009A,
+    Otm8009AConfig, GAMMA_ENTRY_MAX,
+};
+
+/// Arbitrary tables, different from the reference and between polarities
+const CUSTOM: Gamma = Gamma {
+    positive: [
+        0x00, 0x08, 0x10, 0x0c, 0x06, 0x0e, 0x0a, 0x09, 0x03, 0x06, 0x0a, 0x07, 0x0e, 0x0f, 0x09,
+        0x01,
+    ],
+    negative: [
+        0x01, 0x0a, 0x0e, 0x0d, 0x07, 0x0f, 0x0b, 0x0a, 0x05, 0x08, 0x0c, 0x09, 0x10, 0x11, 0x0b,
+        0x02,
+    ],
+};
+
+fn portrait() -> Otm8009AConfig {
+    config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb)
+}
+
+#[test]
+fn reference_table_is_the_default() {
+    assert!(Gamma::GAMMA_2_2.is_valid());
+    assert_eq!(Gamma::GAMMA_2_2.positive, Gamma::GAMMA_2_2.negative);
+    assert_eq!(Gamma::default(), Gamma::GAMMA_2_2);
+    assert!(CUSTOM.is_valid());
+}
+
+#[test]
+fn init_writes_configured_tables() {
+    let gamma = CUSTOM;
+    let dsi = MockDsi::new();
+    Otm8009A::new()
+        .init(
+            &mut dsi.clone(),
+            Otm8009AConfig {
+                gamma,
+                ..portrait()
+            },
+            &mut dsi.delay(),
+        )
+        .unwrap();
+    let transactions = dsi.transactions();
+    assert!(transactions.contains(&long(0xe1, &gamma.positive)));
+    assert!(transactions.contains(&long(0xe2, &gamma.negative)));
+}
+
+#[test]
+fn set_gamma_writes_both_tables_like_init() {
+    let (mut panel, dsi) = init(portrait());
+    panel.set_gamma(&mut dsi.clone(), CUSTOM).unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            short(0x00, 0x00),
+            long(0xe1, &CUSTOM.positive),
+            short(0x00, 0x00),
+            long(0xe2, &CUSTOM.negative),
+        ]
+    );
+    assert_eq!(panel.config().unwrap().gamma, CUSTOM);
+}
+
+#[test]
+fn set_gamma_rejects_out_of_range_entries() {
+    let (mut panel, dsi) = init(portrait());
+    let mut gamma = Gamma::GAMMA_2_2;
+    gamma.negative[3] = GAMMA_ENTRY_MAX + 1;
+    assert_eq!(
+        panel.set_gamma(&mut dsi.clone(), gamma),
+        Err(Error::InvalidConfig(ConfigError::GammaRange))
+    );
+    assert!(dsi.transactions().is_empty());
+    assert_eq!(panel.config().unwrap().gamma, Gamma::GAMMA_2_2);
+
+    assert_eq!(
+        Otm8009A::new().set_gamma(&mut dsi.clone(), Gamma::GAMMA_2_2),
+        Err(Error::PowerState)
+    );
+}
+
+#[test]
+fn gamma_readback() {
+    let dsi = MockDsi::new();
+    dsi.push_reply(&CUSTOM.positive);
+    dsi.push_reply(&CUSTOM.negative);
+    let mut panel = Otm8009A::new();
+    assert_eq!(panel.gamma(&mut dsi.clone()), Ok(CUSTOM));
+
+    let read = |arg| Transaction::Read {
+        command: ReadCommand::DcsShort { arg },
+        len: 16,
+    };
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            short(0x00, 0x00),
+            Transaction::Write(WriteCommand::SetMaximumReturnPacketSize(16)),
+            read(0xe1),
+            short(0x00, 0x00),
+            Transaction::Write(WriteCommand::SetMaximumReturnPacketSize(16)),
+            read(0xe2),
+        ]
+    );
+}
+
+#[test]
+fn digital_gamma_from_gains() {
+    let identity = DigitalGamma::IDENTITY;
+    assert_eq!(identity.red[..4], [0, 8, 16, 24]);
+    assert_eq!(identity.red[31..], [248, 255]);
+    assert_eq!(DigitalGamma::from_gains(1.0, 1.0, 1.0), Ok(identity));
+
+    let warm = DigitalGamma::from_gains(1.0, 0.95, 0.8).unwrap();
+    assert_eq!(warm.red, identity.red);
+    assert_eq!(warm.green[32], 242);
+    assert_eq!(warm.blue[32], 204);
+    assert_eq!(warm.blue[1], 6);
+    assert!(warm.blue.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    for gain in [-0.1, 1.1, f32::NAN] {
+        assert_eq!(
+            DigitalGamma::from_gains(1.0, gain, 1.0),
+            Err(GammaError::Gain)
+        );
+    }
+}
+
+#[test]
+fn set_digital_gamma_writes_every_channel_in_cmd2() {
+    let (mut panel, dsi) = init(portrait());
+    let gamma = DigitalGamma::from_gains(0.9, 1.0, 0.95).unwrap();
+    panel.set_digital_gamma(&mut dsi.clone(), gamma).unwrap();
+    let mut expected = unlock();
+    expected.extend([
+        short(0x00, 0x00),
+        long(0xec, &gamma.red),
+        short(0x00, 0x00),
+        long(0xed, &gamma.green),
+        short(0x00, 0x00),
+        long(0xee, &gamma.blue),
+    ]);
+    expected.extend(lock());
+    assert_eq!(dsi.transactions(), expected);
+    assert_eq!(panel.config().unwrap().digital_gamma, Some(gamma));
+
+    // Replayed by init from the stored configuration
+    let config = *panel.config().unwrap();
+    dsi.clear();
+    panel
+        .init(&mut dsi.clone(), config, &mut dsi.delay())
+        .unwrap();
+    assert!(dsi.transactions().contains(&long(0xee, &gamma.blue)));
+}
+
+#[test]
+fn digital_gamma_readback() {
+    let dsi = MockDsi::new();
+    let gamma = DigitalGamma::from_gains(0.5, 0.75, 1.0).unwrap();
+    for table in [gamma.red, gamma.green, gamma.blue] {
+        dsi.push_reply(&table);
+    }
+    let mut panel = Otm8009A::new();
+    assert_eq!(panel.digital_gamma(&mut dsi.clone()), Ok(gamma));
+    let reads: Vec<_> = dsi
+        .transactions()
+        .into_iter()
+        .filter_map(|t| match t {
+            Transaction::Read { command, len } => Some((command, len)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        reads,
+        [0xec, 0xed, 0xee].map(|arg| (ReadCommand::DcsShort { arg }, 33))
+    );
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use common::{config, init, long};
+use otm8009a::mock::{MockDsi, ReadCommand, Transaction, WriteCommand};
+use otm8009a::{ColorMap, Error, FrameRate, Mode, Otm8009A, Otm8009AConfig, PixelFormat, Window};
+
+fn max_return(size: u16) -> Transaction {
+    Transaction::Write(WriteCommand::SetMaximumReturnPacketSize(size))
+}
+
+fn read(arg: u8, len: usize) -> Transaction {
+    Transaction::Read {
+        command: ReadCommand::DcsShort { arg },
+        len,
+    }
+}
+
+#[test]
+fn set_window_writes_caset_paset() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb));
+    panel
+        .set_window(&mut dsi.clone(), 10, 20, 799, 479)
+        .unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            long(0x2a, &[0x00, 0x0a, 0x03, 0x1f]),
+            long(0x2b, &[0x00, 0x14, 0x01, 0xdf]),
+        ]
+    );
+}
+
+#[test]
+fn set_window_is_bounds_checked() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    for (x0, y0, x1, y1) in [
+        (0, 0, 480, 10),
+        (0, 0, 10, 800),
+        (5, 0, 4, 10),
+        (0, 5, 10, 4),
+    ] {
+        assert_eq!(
+            panel.set_window(&mut dsi.clone(), x0, y0, x1, y1),
+            Err(Error::OutOfBounds)
+        );
+    }
+    assert!(dsi.transactions().is_empty());
+
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.set_window(&mut dsi.clone(), 0, 0, 1, 1),
+        Err(Error::PowerState)
+    );
+}
+
+#[test]
+fn write_pixels_is_chunked_to_whole_pixels() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    let pixels: Vec<u8> = (0..21).collect();
+    // 9 bytes of payload fit next to the command: three RGB888 pixels
+    panel.write_pixels(&mut dsi.clone(), &pixels, 10).unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            long(0x2c, &pixels[..9]),
+            long(0x3c, &pixels[9..18]),
+            long(0x3c, &pixels[18..]),
+        ]
+    );
+}
+
+#[test]
+fn write_pixels_rgb565() {
+    let (mut panel, dsi) = init(Otm8009AConfig {
+        pixel_format: PixelFormat::Rgb565,
+        ..config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb)
+    });
+    let pixels = [0xf8, 0x00, 0x07, 0xe0, 0x00, 0x1f];
+    panel.write_pixels(&mut dsi.clone(), &pixels, 6).unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![long(0x2c, &pixels[..4]), long(0x3c, &pixels[4..])]
+    );
+
+    assert_eq!(
+        panel.write_pixels(&mut dsi.clone(), &pixels[..3], 64),
+        Err(Error::WriteLength)
+    );
+    assert_eq!(
+        panel.write_pixels(&mut dsi.clone(), &pixels, 2),
+        Err(Error::WriteLength)
+    );
+}
+
+#[test]
+fn write_pixels_needs_an_awake_panel() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.write_pixels(&mut dsi.clone(), &[0; 3], 64),
+        Err(Error::PowerState)
+    );
+
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    panel.sleep(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    dsi.clear();
+    assert_eq!(
+        panel.write_pixels(&mut dsi.clone(), &[0; 3], 64),
+        Err(Error::PowerState)
+    );
+    assert!(dsi.transactions().is_empty());
+}
+
+#[test]
+fn read_pixels_is_chunked_to_whole_pixels() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    let gram: Vec<u8> = (0..15).collect();
+    dsi.push_reply(&gram[..6]);
+    dsi.push_reply(&gram[6..12]);
+    dsi.push_reply(&gram[12..]);
+    let mut buf = [0u8; 15];
+    // 7 bytes of return packet hold two RGB888 pixels
+    panel
+        .read_pixels(&mut dsi.clone(), Window::new(1, 2, 5, 2), &mut buf, 7)
+        .unwrap();
+    assert_eq!(buf[..], gram[..]);
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            long(0x2a, &[0x00, 0x01, 0x00, 0x05]),
+            long(0x2b, &[0x00, 0x02, 0x00, 0x02]),
+            max_return(6),
+            read(0x2e, 6),
+            read(0x3e, 6),
+            max_return(3),
+            read(0x3e, 3),
+        ]
+    );
+}
+
+#[test]
+fn read_pixels_decodes_rgb565() {
+    let (mut panel, dsi) = init(Otm8009AConfig {
+        pixel_format: PixelFormat::Rgb565,
+        ..config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb)
+    });
+    dsi.push_reply(&[0xff, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0xff]);
+    let mut buf = [0u8; 6];
+    panel
+        .read_pixels(&mut dsi.clone(), Window::new(0, 0, 0, 2), &mut buf, 64)
+        .unwrap();
+    assert_eq!(buf, [0xf8, 0x00, 0x07, 0xe0, 0x00, 0x1f]);
+    assert_eq!(dsi.transactions()[2..], [max_return(9), read(0x2e, 9)]);
+}
+
+#[test]
+fn read_pixels_checks_its_arguments() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    let mut buf = [0u8; 6];
+    assert_eq!(
+        panel.read_pixels(&mut dsi.clone(), Window::new(0, 0, 2, 0), &mut buf, 64),
+        Err(Error::ReadLength)
+    );
+    assert_eq!(
+        panel.read_pixels(&mut dsi.clone(), Window::new(0, 0, 1, 0), &mut buf, 2),
+        Err(Error::ReadLength)
+    );
+    assert_eq!(
+        panel.read_pixels(&mut dsi.clone(), Window::new(479, 0, 480, 0), &mut buf, 64),
+        Err(Error::OutOfBounds)
+    );
+    assert!(dsi.transactions().is_empty());
+
+    panel.sleep(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    assert_eq!(
+        panel.read_pixels(&mut dsi.clone(), Window::new(0, 0, 1, 0), &mut buf, 64),
+        Err(Error::PowerState)
+    );
+}
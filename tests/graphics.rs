@@ -0,0 +1,125 @@
+mod common;
+
+use common::{config, init, long, short};
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{OriginDimensions, Point, Size};
+use embedded_graphics_core::pixelcolor::{Rgb565, Rgb888, RgbColor};
+use embedded_graphics_core::primitives::Rectangle;
+use embedded_graphics_core::Pixel;
+use otm8009a::graphics::Otm8009ADisplay;
+use otm8009a::mock::MockDsi;
+use otm8009a::{ColorMap, Error, FrameRate, Mode, Otm8009A, PixelFormat};
+
+fn display<C: otm8009a::graphics::GramColor>(
+    max_packet_size: u16,
+) -> (Otm8009ADisplay<MockDsi, C>, MockDsi) {
+    let (panel, dsi) = init(config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb));
+    let display = Otm8009ADisplay::new(dsi.clone(), panel, max_packet_size).unwrap();
+    dsi.clear();
+    (display, dsi)
+}
+
+#[test]
+fn new_sets_pixel_format() {
+    let (panel, dsi) = init(config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb));
+    let display = Otm8009ADisplay::<_, Rgb565>::new(dsi.clone(), panel, 64).unwrap();
+    assert_eq!(dsi.transactions(), vec![short(0x3a, 0x55)]);
+    assert_eq!(display.size(), Size::new(800, 480));
+    assert_eq!(
+        display.panel().config().unwrap().pixel_format,
+        PixelFormat::Rgb565
+    );
+
+    let dsi = MockDsi::new();
+    assert!(matches!(
+        Otm8009ADisplay::<_, Rgb888>::new(dsi.clone(), Otm8009A::new(), 64),
+        Err(Error::PowerState)
+    ));
+}
+
+#[test]
+fn fill_solid_streams_one_window() {
+    let (mut display, dsi) = display::<Rgb888>(7);
+    display
+        .fill_solid(
+            &Rectangle::new(Point::new(1, 2), Size::new(2, 2)),
+            Rgb888::RED,
+        )
+        .unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            long(0x2a, &[0x00, 0x01, 0x00, 0x02]),
+            long(0x2b, &[0x00, 0x02, 0x00, 0x03]),
+            long(0x2c, &[0xff, 0x00, 0x00, 0xff, 0x00, 0x00]),
+            long(0x3c, &[0xff, 0x00, 0x00, 0xff, 0x00, 0x00]),
+        ]
+    );
+}
+
+#[test]
+fn fill_solid_is_clipped() {
+    let (mut display, dsi) = display::<Rgb565>(64);
+    display
+        .fill_solid(
+            &Rectangle::new(Point::new(798, -1), Size::new(4, 2)),
+            Rgb565::BLUE,
+        )
+        .unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            long(0x2a, &[0x03, 0x1e, 0x03, 0x1f]),
+            long(0x2b, &[0x00, 0x00, 0x00, 0x00]),
+            long(0x2c, &[0x00, 0x1f, 0x00, 0x1f]),
+        ]
+    );
+
+    dsi.clear();
+    display
+        .fill_solid(
+            &Rectangle::new(Point::new(800, 0), Size::new(4, 2)),
+            Rgb565::BLUE,
+        )
+        .unwrap();
+    assert!(dsi.transactions().is_empty());
+}
+
+#[test]
+fn fill_contiguous_rgb565_byte_order() {
+    let (mut display, dsi) = display::<Rgb565>(64);
+    display
+        .fill_contiguous(
+            &Rectangle::new(Point::zero(), Size::new(3, 1)),
+            [Rgb565::RED, Rgb565::GREEN, Rgb565::BLUE],
+        )
+        .unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            long(0x2a, &[0x00, 0x00, 0x00, 0x02]),
+            long(0x2b, &[0x00, 0x00, 0x00, 0x00]),
+            long(0x2c, &[0xf8, 0x00, 0x07, 0xe0, 0x00, 0x1f]),
+        ]
+    );
+}
+
+#[test]
+fn draw_iter_uses_single_pixel_windows() {
+    let (mut display, dsi) = display::<Rgb888>(64);
+    display
+        .draw_iter([
+            Pixel(Point::new(3, 4), Rgb888::WHITE),
+            Pixel(Point::new(-1, 0), Rgb888::WHITE),
+            Pixel(Point::new(0, 480), Rgb888::WHITE),
+        ])
+        .unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            long(0x2a, &[0x00, 0x03, 0x00, 0x03]),
+            long(0x2b, &[0x00, 0x04, 0x00, 0x04]),
+            long(0x2c, &[0xff, 0xff, 0xff]),
+        ]
+    );
+}
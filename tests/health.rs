@@ -0,0 +1,175 @@
+mod common;
+
+use common::{config, init, short};
+use otm8009a::mock::{MockDsi, MockError, ReadCommand, Transaction};
+use otm8009a::{
+    ColorMap, Error, FrameRate, HealthFault, Mode, Otm8009A, PowerMode, PowerState, SelfDiagnostic,
+};
+
+/// RDDPM, RDDSDR, RDDMADCTL and RDDCOLMOD of a healthy panel right after `init`
+const HEALTHY: [u8; 4] = [0x9c, 0xc0, 0x60, 0x77];
+
+/// Scripts the register replies of a fresh `health_check`, dropping any
+/// left over from a check that returned early
+fn push_replies(dsi: &MockDsi, replies: [u8; 4]) {
+    dsi.clear();
+    for reply in replies {
+        dsi.push_reply(&[reply]);
+    }
+}
+
+fn landscape() -> (Otm8009A, MockDsi) {
+    init(config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb))
+}
+
+#[test]
+fn healthy_panel_passes() {
+    let (mut panel, dsi) = landscape();
+    push_replies(&dsi, HEALTHY);
+    assert_eq!(panel.health_check(&mut dsi.clone()), Ok(None));
+    assert_eq!(
+        dsi.transactions(),
+        [0x0a, 0x0f, 0x0b, 0x0c].map(|arg| Transaction::Read {
+            command: ReadCommand::DcsShort { arg },
+            len: 1,
+        })
+    );
+}
+
+#[test]
+fn every_check_can_trip() {
+    let (mut panel, dsi) = landscape();
+
+    // Dropped back into sleep with the booster off
+    push_replies(&dsi, [0x0c, 0xc0, 0x60, 0x77]);
+    assert_eq!(
+        panel.health_check(&mut dsi.clone()),
+        Ok(Some(HealthFault::PowerMode {
+            expected: PowerMode {
+                booster_on: true,
+                idle_mode: false,
+                partial_mode: false,
+                sleep_out: true,
+                normal_mode: true,
+                display_on: true,
+            },
+            actual: PowerMode {
+                booster_on: false,
+                idle_mode: false,
+                partial_mode: false,
+                sleep_out: false,
+                normal_mode: true,
+                display_on: true,
+            },
+        }))
+    );
+
+    push_replies(&dsi, [0x9c, 0x40, 0x60, 0x77]);
+    assert_eq!(
+        panel.health_check(&mut dsi.clone()),
+        Ok(Some(HealthFault::SelfDiagnostic(SelfDiagnostic {
+            functionality: true,
+            ..SelfDiagnostic::default()
+        })))
+    );
+
+    push_replies(&dsi, [0x9c, 0xc0, 0x00, 0x77]);
+    assert_eq!(
+        panel.health_check(&mut dsi.clone()),
+        Ok(Some(HealthFault::AddressMode {
+            expected: 0x60,
+            actual: 0x00,
+        }))
+    );
+
+    push_replies(&dsi, [0x9c, 0xc0, 0x60, 0x66]);
+    assert_eq!(
+        panel.health_check(&mut dsi.clone()),
+        Ok(Some(HealthFault::PixelFormat {
+            expected: 0x77,
+            actual: 0x66,
+        }))
+    );
+}
+
+#[test]
+fn expected_state_follows_the_driver() {
+    let (mut panel, dsi) = landscape();
+    panel.display_off(&mut dsi.clone()).unwrap();
+    panel.enter_idle_mode(&mut dsi.clone()).unwrap();
+    push_replies(&dsi, [0xd8, 0xc0, 0x60, 0x77]);
+    assert_eq!(panel.health_check(&mut dsi.clone()), Ok(None));
+
+    // Every sleep out inverts the self-diagnostic bits
+    panel.sleep(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    panel.wake(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    push_replies(&dsi, [0xd8, 0x00, 0x60, 0x77]);
+    assert_eq!(panel.health_check(&mut dsi.clone()), Ok(None));
+}
+
+#[test]
+fn recover_reinitialises_only_on_fault() {
+    let (mut panel, dsi) = landscape();
+    push_replies(&dsi, HEALTHY);
+    assert_eq!(panel.recover(&mut dsi.clone(), &mut dsi.delay()), Ok(None));
+    assert_eq!(dsi.transactions().len(), 4);
+
+    panel.display_off(&mut dsi.clone()).unwrap();
+    dsi.clear();
+    dsi.push_reply(&[0x00]);
+    assert!(matches!(
+        panel.recover(&mut dsi.clone(), &mut dsi.delay()),
+        Ok(Some(HealthFault::PowerMode { .. }))
+    ));
+    let transactions = dsi.transactions();
+    assert_eq!(
+        transactions[1..3],
+        [short(0x01, 0x00), Transaction::Delay(120)]
+    );
+
+    // The stored configuration was replayed
+    let reference = MockDsi::new();
+    Otm8009A::new()
+        .init(
+            &mut reference.clone(),
+            config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb),
+            &mut reference.delay(),
+        )
+        .unwrap();
+    assert_eq!(transactions[3..], reference.transactions()[..]);
+
+    push_replies(&dsi, HEALTHY);
+    assert_eq!(panel.health_check(&mut dsi.clone()), Ok(None));
+}
+
+#[test]
+fn unanswered_read_is_a_fault() {
+    let (mut panel, dsi) = landscape();
+    push_replies(&dsi, HEALTHY);
+    dsi.fail_at(1);
+    assert_eq!(
+        panel.health_check(&mut dsi.clone()),
+        Ok(Some(HealthFault::NoResponse(MockError::Injected)))
+    );
+
+    // No reply at all, the panel is reset and brought up again
+    dsi.clear();
+    assert_eq!(
+        panel.recover(&mut dsi.clone(), &mut dsi.delay()),
+        Ok(Some(HealthFault::NoResponse(MockError::NoReply)))
+    );
+    assert_eq!(
+        dsi.transactions()[1..3],
+        [short(0x01, 0x00), Transaction::Delay(120)]
+    );
+    assert_eq!(panel.power_state(), PowerState::DisplayOn);
+}
+
+#[test]
+fn health_check_needs_init() {
+    let dsi = MockDsi::new();
+    assert_eq!(
+        Otm8009A::new().health_check(&mut dsi.clone()),
+        Err(Error::PowerState)
+    );
+}
@@ -0,0 +1,507 @@
+mod common;
+
+use common::{config, long, short};
+use otm8009a::mock::{MockDsi, MockError, ReadCommand, Transaction, WriteCommand};
+use otm8009a::{
+    ColorMap, ConfigError, Ddb, Error, FrameRate, Mode, Orientation, Otm8009A, Otm8009AConfig,
+    PanelId, PixelFormat, Rotation,
+};
+
+const FRAME_RATES: [FrameRate; 8] = [
+    FrameRate::_35Hz,
+    FrameRate::_40Hz,
+    FrameRate::_45Hz,
+    FrameRate::_50Hz,
+    FrameRate::_55Hz,
+    FrameRate::_60Hz,
+    FrameRate::_65Hz,
+    FrameRate::_70Hz,
+];
+
+/// Reference byte stream of `init`, as captured from the original hand-written sequence
+fn expected_init(frame_rate: u8, madctr: u8, caset: [u8; 4], paset: [u8; 4]) -> Vec<Transaction> {
+    vec![
+        short(0x00, 0x00),
+        long(0xff, &[0x80, 0x09, 0x01]),
+        short(0x00, 0x80),
+        long(0xff, &[0x80, 0x09]),
+        short(0x00, 0x80),
+        short(0xc4, 0x30),
+        Transaction::Delay(10),
+        short(0x00, 0x8a),
+        short(0xc4, 0x40),
+        Transaction::Delay(10),
+        short(0x00, 0xb1),
+        short(0xc5, 0xa9),
+        short(0x00, 0x91),
+        short(0xc5, 0x34),
+        short(0x00, 0xb4),
+        short(0xc0, 0x50),
+        short(0x00, 0x00),
+        short(0xd9, 0x4e),
+        short(0x00, 0x81),
+        short(0xc1, frame_rate),
+        short(0x00, 0xa1),
+        short(0xc1, 0x08),
+        short(0x00, 0x92),
+        short(0xc5, 0x01),
+        short(0x00, 0x95),
+        short(0xc5, 0x34),
+        short(0x00, 0x00),
+        long(0xd8, &[0x79, 0x79]),
+        short(0x00, 0x94),
+        short(0xc5, 0x33),
+        short(0x00, 0xa3),
+        short(0xc0, 0x1b),
+        short(0x00, 0x82),
+        short(0xc5, 0x83),
+        short(0x00, 0x81),
+        short(0xc4, 0x83),
+        short(0x00, 0xa1),
+        short(0xc1, 0x0e),
+        short(0x00, 0xa6),
+        long(0xb3, &[0x00, 0x01]),
+        short(0x00, 0x80),
+        long(0xce, &[0x85, 0x01, 0x00, 0x84, 0x01, 0x00]),
+        short(0x00, 0xa0),
+        long(
+            0xce,
+            &[
+                0x18, 0x04, 0x03, 0x39, 0x00, 0x00, 0x00, 0x18, 0x03, 0x03, 0x3A, 0x00, 0x00, 0x00,
+            ],
+        ),
+        short(0x00, 0xb0),
+        long(
+            0xce,
+            &[
+                0x18, 0x02, 0x03, 0x3B, 0x00, 0x00, 0x00, 0x18, 0x01, 0x03, 0x3C, 0x00, 0x00, 0x00,
+            ],
+        ),
+        short(0x00, 0xc0),
+        long(
+            0xcf,
+            &[0x01, 0x01, 0x20, 0x20, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00],
+        ),
+        short(0x00, 0xd0),
+        short(0xcf, 0x00),
+        short(0x00, 0x80),
+        long(0xcb, &[0u8; 10]),
+        short(0x00, 0x90),
+        long(0xcb, &[0u8; 15]),
+        short(0x00, 0xa0),
+        long(0xcb, &[0u8; 15]),
+        short(0x00, 0xb0),
+        long(0xcb, &[0u8; 10]),
+        short(0x00, 0xc0),
+        long(
+            0xcb,
+            &[
+                0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00,
+            ],
+        ),
+        short(0x00, 0xd0),
+        long(
+            0xcb,
+            &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00,
+                0x00,
+            ],
+        ),
+        short(0x00, 0xe0),
+        long(0xcb, &[0u8; 10]),
+        short(0x00, 0xf0),
+        long(0xcb, &[255u8; 10]),
+        short(0x00, 0x80),
+        long(
+            0xcc,
+            &[0x00, 0x26, 0x09, 0x0B, 0x01, 0x25, 0x00, 0x00, 0x00, 0x00],
+        ),
+        short(0x00, 0x90),
+        long(
+            0xcc,
+            &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x26, 0x0A, 0x0C,
+                0x02,
+            ],
+        ),
+        short(0x00, 0xa0),
+        long(
+            0xcc,
+            &[
+                0x25, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00,
+            ],
+        ),
+        short(0x00, 0xb0),
+        long(
+            0xcc,
+            &[0x00, 0x25, 0x0C, 0x0A, 0x02, 0x26, 0x00, 0x00, 0x00, 0x00],
+        ),
+        short(0x00, 0xc0),
+        long(
+            0xcc,
+            &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x25, 0x0B, 0x09,
+                0x01,
+            ],
+        ),
+        short(0x00, 0xd0),
+        long(
+            0xcc,
+            &[
+                0x26, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00,
+            ],
+        ),
+        short(0x00, 0x81),
+        short(0xc5, 0x66),
+        short(0x00, 0xb6),
+        short(0xf5, 0x06),
+        short(0x00, 0xb1),
+        short(0xc6, 0x06),
+        short(0x00, 0x00),
+        long(0xff, &[0xff, 0xff, 0xff]),
+        short(0x00, 0x00),
+        short(0x00, 0x00),
+        long(
+            0xe1,
+            &[
+                0x00, 0x09, 0x0F, 0x0E, 0x07, 0x10, 0x0B, 0x0A, 0x04, 0x07, 0x0B, 0x08, 0x0F, 0x10,
+                0x0A, 0x01,
+            ],
+        ),
+        short(0x00, 0x00),
+        long(
+            0xe2,
+            &[
+                0x00, 0x09, 0x0F, 0x0E, 0x07, 0x10, 0x0B, 0x0A, 0x04, 0x07, 0x0B, 0x08, 0x0F, 0x10,
+                0x0A, 0x01,
+            ],
+        ),
+        short(0x11, 0x00),
+        Transaction::Delay(120),
+        short(0x3a, 0x77),
+        short(0x36, madctr),
+        long(0x2a, &caset),
+        long(0x2b, &paset),
+        short(0x51, 0x7f),
+        short(0x53, 0x2c),
+        short(0x55, 0x02),
+        short(0x5e, 0xff),
+        short(0x29, 0x00),
+        short(0x00, 0x00),
+        short(0x2c, 0x00),
+    ]
+}
+
+#[test]
+fn init_byte_stream_for_every_configuration() {
+    for (mode, color_map, madctr) in [
+        (Mode::Portrait, ColorMap::Rgb, 0x00),
+        (Mode::Portrait, ColorMap::Bgr, 0x08),
+        (Mode::Landscape, ColorMap::Rgb, 0x60),
+        (Mode::Landscape, ColorMap::Bgr, 0x68),
+    ] {
+        for (i, frame_rate) in FRAME_RATES.into_iter().enumerate() {
+            let dsi = MockDsi::new();
+            let mut panel = Otm8009A::new();
+            panel
+                .init(
+                    &mut dsi.clone(),
+                    config(frame_rate, mode, color_map),
+                    &mut dsi.delay(),
+                )
+                .unwrap();
+
+            let (caset, paset) = match mode {
+                Mode::Portrait => ([0x00, 0x00, 0x01, 0xdf], [0x00, 0x00, 0x03, 0x1f]),
+                Mode::Landscape => ([0x00, 0x00, 0x03, 0x1f], [0x00, 0x00, 0x01, 0xdf]),
+            };
+            let frame_rate = (i as u8) << 4 | i as u8;
+            assert_eq!(
+                dsi.transactions(),
+                expected_init(frame_rate, madctr, caset, paset),
+                "{mode:?} {color_map:?} {i}"
+            );
+        }
+    }
+}
+
+#[test]
+fn init_stops_at_first_error() {
+    let dsi = MockDsi::new();
+    dsi.fail_at(6);
+    let mut panel = Otm8009A::new();
+    let result = panel.init(
+        &mut dsi.clone(),
+        config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb),
+        &mut dsi.delay(),
+    );
+    assert_eq!(result, Err(Error::Dsi(MockError::Injected)));
+
+    // Six writes went through, with the first delay in between
+    let expected = expected_init(0x55, 0, [0; 4], [0; 4]);
+    assert_eq!(dsi.transactions(), expected[..7]);
+}
+
+#[test]
+fn enable_te_output_writes_scanline_then_teeon() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    panel.enable_te_output(533, &mut dsi.clone()).unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![long(0x44, &[0x02, 0x15]), short(0x35, 0x00)]
+    );
+}
+
+#[test]
+fn id_matches_reads_id1() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+
+    dsi.push_reply(&[0x40]);
+    assert_eq!(panel.id_matches(&mut dsi.clone()), Ok(()));
+    dsi.push_reply(&[0x00]);
+    assert_eq!(
+        panel.id_matches(&mut dsi.clone()),
+        Err(Error::WrongPanelId(0x00))
+    );
+
+    let read = Transaction::Read {
+        command: ReadCommand::DcsShort { arg: 0xda },
+        len: 1,
+    };
+    assert_eq!(dsi.transactions(), vec![read.clone(), read]);
+}
+
+#[test]
+fn read_id_reads_every_id_register() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    dsi.push_reply(&[0x40]);
+    dsi.push_reply(&[0x00]);
+    dsi.push_reply(&[0x02]);
+    dsi.push_reply(&[0x40, 0x00, 0x02]);
+    dsi.push_reply(&[0x01, 0x8b, 0x80, 0x09, 0xff]);
+
+    let id = panel.read_id(&mut dsi.clone()).unwrap();
+    assert_eq!(
+        id,
+        PanelId {
+            id1: 0x40,
+            id2: 0x00,
+            id3: 0x02,
+            rddid: [0x40, 0x00, 0x02],
+            ddb: Ddb {
+                supplier_id: 0x018b,
+                supplier_elective_data: 0x8009,
+            },
+        }
+    );
+    assert!(id.is_otm8009a());
+
+    let read = |arg, len| Transaction::Read {
+        command: ReadCommand::DcsShort { arg },
+        len,
+    };
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            read(0xda, 1),
+            read(0xdb, 1),
+            read(0xdc, 1),
+            Transaction::Write(WriteCommand::SetMaximumReturnPacketSize(3)),
+            read(0x04, 3),
+            Transaction::Write(WriteCommand::SetMaximumReturnPacketSize(5)),
+            read(0xa1, 5),
+        ]
+    );
+}
+
+#[test]
+fn read_without_reply_is_an_error() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.id_matches(&mut dsi.clone()),
+        Err(Error::Dsi(MockError::NoReply))
+    );
+}
+
+#[test]
+fn init_with_custom_sequence() {
+    use otm8009a::sequence::{ConfigParam, InitStep};
+
+    const SEQUENCE: &[InitStep] = &[
+        InitStep::Shift(0x81),
+        InitStep::Config(0xc1, ConfigParam::FrameRate),
+        InitStep::Delay(5),
+        InitStep::Long(0xd8, &[0x70, 0x70]),
+    ];
+
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    panel
+        .init_with_sequence(
+            &mut dsi.clone(),
+            config(FrameRate::_35Hz, Mode::Portrait, ColorMap::Rgb),
+            SEQUENCE,
+            &mut dsi.delay(),
+        )
+        .unwrap();
+
+    let default = expected_init(0x00, 0x00, [0, 0, 0x01, 0xdf], [0, 0, 0x03, 0x1f]);
+    let mut expected = vec![
+        short(0x00, 0x81),
+        short(0xc1, 0x00),
+        Transaction::Delay(5),
+        long(0xd8, &[0x70, 0x70]),
+    ];
+    // Everything after the vendor part stays the same
+    let tail = default
+        .iter()
+        .position(|t| *t == long(0xff, &[0xff; 3]))
+        .unwrap()
+        + 1;
+    expected.extend_from_slice(&default[tail..]);
+    assert_eq!(dsi.transactions(), expected);
+}
+
+#[test]
+fn invalid_config_is_rejected_before_any_traffic() {
+    for (mode, cols, rows, error) in [
+        (Mode::Portrait, 0, 800, ConfigError::ZeroSize),
+        (Mode::Landscape, 800, 0, ConfigError::ZeroSize),
+        (Mode::Portrait, 480, 801, ConfigError::TooLarge),
+        (Mode::Landscape, 1024, 480, ConfigError::TooLarge),
+        (Mode::Portrait, 800, 480, ConfigError::OrientationMismatch),
+        (Mode::Landscape, 480, 800, ConfigError::OrientationMismatch),
+    ] {
+        let config = Otm8009AConfig {
+            cols,
+            rows,
+            ..config(FrameRate::_60Hz, mode, ColorMap::Rgb)
+        };
+        assert_eq!(config.validate(), Err(error));
+
+        let dsi = MockDsi::new();
+        let mut panel = Otm8009A::new();
+        assert_eq!(
+            panel.init(&mut dsi.clone(), config, &mut dsi.delay()),
+            Err(Error::InvalidConfig(error))
+        );
+        assert!(dsi.transactions().is_empty());
+    }
+}
+
+#[test]
+fn smaller_resolution_is_accepted() {
+    let config = Otm8009AConfig {
+        cols: 320,
+        rows: 240,
+        ..config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb)
+    };
+    assert_eq!(config.validate(), Ok(()));
+}
+
+#[test]
+fn init_sends_configured_pixel_format() {
+    for (pixel_format, colmod) in [
+        (PixelFormat::Rgb565, 0x55),
+        (PixelFormat::Rgb666, 0x66),
+        (PixelFormat::Rgb888, 0x77),
+        (PixelFormat::Rgb888_3T, 0xe7),
+    ] {
+        let dsi = MockDsi::new();
+        let mut panel = Otm8009A::new();
+        let config = Otm8009AConfig {
+            pixel_format,
+            ..config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb)
+        };
+        panel
+            .init(&mut dsi.clone(), config, &mut dsi.delay())
+            .unwrap();
+        assert!(dsi.transactions().contains(&short(0x3a, colmod)));
+        assert_eq!(panel.config(), Some(&config));
+    }
+}
+
+#[test]
+fn set_pixel_format_at_runtime() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.set_pixel_format(&mut dsi.clone(), PixelFormat::Rgb565),
+        Err(Error::PowerState)
+    );
+
+    panel
+        .init(
+            &mut dsi.clone(),
+            config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb),
+            &mut dsi.delay(),
+        )
+        .unwrap();
+    dsi.clear();
+    panel
+        .set_pixel_format(&mut dsi.clone(), PixelFormat::Rgb565)
+        .unwrap();
+    assert_eq!(dsi.transactions(), vec![short(0x3a, 0x55)]);
+    assert_eq!(panel.config().unwrap().pixel_format, PixelFormat::Rgb565);
+}
+
+#[test]
+fn init_sends_madctr_for_every_orientation() {
+    for (rotation, madctr) in [
+        (Rotation::Deg0, [0x00, 0x40, 0x80, 0xc0]),
+        (Rotation::Deg90, [0x60, 0xe0, 0x20, 0xa0]),
+        (Rotation::Deg180, [0xc0, 0x80, 0x40, 0x00]),
+        (Rotation::Deg270, [0xa0, 0x20, 0xe0, 0x60]),
+    ] {
+        for (i, (flip_horizontal, flip_vertical)) in
+            [(false, false), (true, false), (false, true), (true, true)]
+                .into_iter()
+                .enumerate()
+        {
+            let orientation = Orientation {
+                rotation,
+                flip_horizontal,
+                flip_vertical,
+            };
+            let (cols, rows) = if orientation.swaps_axes() {
+                (800, 480)
+            } else {
+                (480, 800)
+            };
+            for (color_map, bgr) in [(ColorMap::Rgb, 0x00), (ColorMap::Bgr, 0x08)] {
+                let dsi = MockDsi::new();
+                let mut panel = Otm8009A::new();
+                let config = Otm8009AConfig {
+                    orientation,
+                    cols,
+                    rows,
+                    ..config(FrameRate::_60Hz, Mode::Portrait, color_map)
+                };
+                panel
+                    .init(&mut dsi.clone(), config, &mut dsi.delay())
+                    .unwrap();
+
+                let last_col = (cols - 1).to_be_bytes();
+                let last_row = (rows - 1).to_be_bytes();
+                let expected = expected_init(
+                    0x55,
+                    madctr[i] | bgr,
+                    [0, 0, last_col[0], last_col[1]],
+                    [0, 0, last_row[0], last_row[1]],
+                );
+                assert_eq!(
+                    dsi.transactions(),
+                    expected,
+                    "{orientation:?} {color_map:?}"
+                );
+            }
+        }
+    }
+}
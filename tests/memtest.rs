@@ -0,0 +1,156 @@
+mod common;
+
+use common::{config, init, long, short};
+use otm8009a::mock::{MockDsi, MockError, ReadCommand, Transaction, WriteCommand};
+use otm8009a::{
+    ColorMap, Error, FrameRate, MemoryReport, Mismatch, Mode, Otm8009A, Otm8009AConfig,
+    PixelFormat, TestPattern, Window,
+};
+
+const REGION: Window = Window::new(4, 6, 5, 7);
+
+/// Full-screen portrait window set again after the test
+fn full_screen() -> [Transaction; 2] {
+    [
+        long(0x2a, &[0x00, 0x00, 0x01, 0xdf]),
+        long(0x2b, &[0x00, 0x00, 0x03, 0x1f]),
+    ]
+}
+
+/// Pattern values of `REGION`, in GRAM order
+fn pattern_bytes(pattern: TestPattern) -> Vec<u8> {
+    let mut bytes = vec![];
+    for (index, (x, y)) in [(4, 6), (5, 6), (4, 7), (5, 7)].into_iter().enumerate() {
+        bytes.extend_from_slice(&pattern.value(index, x, y).to_be_bytes()[1..]);
+    }
+    bytes
+}
+
+fn push_readback(dsi: &MockDsi) {
+    for pattern in TestPattern::ALL {
+        dsi.push_reply(&pattern_bytes(pattern));
+    }
+}
+
+#[test]
+fn patterns() {
+    assert_eq!(
+        pattern_bytes(TestPattern::WalkingOnes),
+        [0, 0, 1, 0, 0, 2, 0, 0, 4, 0, 0, 8]
+    );
+    assert_eq!(TestPattern::WalkingOnes.value(23, 0, 0), 0x80_0000);
+    assert_eq!(TestPattern::WalkingOnes.value(24, 0, 0), 1);
+    assert_eq!(TestPattern::WalkingZeros.value(1, 0, 0), 0xff_fffd);
+    assert_eq!(TestPattern::Checkerboard.value(0, 4, 6), 0x55_5555);
+    assert_eq!(TestPattern::Checkerboard.value(1, 5, 6), 0xaa_aaaa);
+    assert_eq!(TestPattern::AddressInData.value(0, 479, 799), 0x31f_1df);
+}
+
+#[test]
+fn memory_check_writes_and_reads_back_every_pattern() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    for pattern in TestPattern::ALL {
+        let bytes = pattern_bytes(pattern);
+        dsi.push_reply(&bytes[..6]);
+        dsi.push_reply(&bytes[6..]);
+    }
+    // Two RGB888 pixels per packet
+    let report = panel.memory_check(&mut dsi.clone(), REGION, 7).unwrap();
+    assert!(report.passed());
+    assert_eq!(report, MemoryReport::default());
+    assert_eq!(report.into_result::<()>(), Ok(()));
+
+    let mut expected = vec![];
+    for pattern in TestPattern::ALL {
+        let bytes = pattern_bytes(pattern);
+        expected.extend([
+            long(0x2a, &[0x00, 0x04, 0x00, 0x05]),
+            long(0x2b, &[0x00, 0x06, 0x00, 0x07]),
+            long(0x2c, &bytes[..6]),
+            long(0x3c, &bytes[6..]),
+            Transaction::Write(WriteCommand::SetMaximumReturnPacketSize(6)),
+        ]);
+        for arg in [0x2e, 0x3e] {
+            expected.push(Transaction::Read {
+                command: ReadCommand::DcsShort { arg },
+                len: 6,
+            });
+        }
+    }
+    expected.extend(full_screen());
+    assert_eq!(dsi.transactions(), expected);
+}
+
+#[test]
+fn memory_check_reports_mismatches() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    dsi.push_reply(&pattern_bytes(TestPattern::WalkingOnes));
+    let mut walking_zeros = pattern_bytes(TestPattern::WalkingZeros);
+    walking_zeros[5] = 0xff;
+    walking_zeros[11] = 0x00;
+    dsi.push_reply(&walking_zeros);
+    dsi.push_reply(&pattern_bytes(TestPattern::Checkerboard));
+    let mut address = pattern_bytes(TestPattern::AddressInData);
+    address[2] = 0xff;
+    dsi.push_reply(&address);
+
+    let report = panel.memory_check(&mut dsi.clone(), REGION, 64).unwrap();
+    assert!(!report.passed());
+    assert_eq!(report.into_result::<()>(), Err(Error::VerifyFailed));
+    assert_eq!(
+        report,
+        MemoryReport {
+            errors: 3,
+            first_mismatch: Some(Mismatch {
+                pattern: TestPattern::WalkingZeros,
+                x: 5,
+                y: 6,
+                expected: 0xff_fffd,
+                actual: 0xff_ffff,
+            }),
+        }
+    );
+}
+
+#[test]
+fn memory_check_restores_pixel_format_and_window() {
+    let (mut panel, dsi) = init(Otm8009AConfig {
+        pixel_format: PixelFormat::Rgb565,
+        ..config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb)
+    });
+    push_readback(&dsi);
+    panel.memory_check(&mut dsi.clone(), REGION, 64).unwrap();
+    let transactions = dsi.transactions();
+    let restore = [&[short(0x3a, 0x55)][..], &full_screen()].concat();
+    assert_eq!(transactions.first(), Some(&short(0x3a, 0x77)));
+    assert_eq!(transactions[transactions.len() - 3..], restore[..]);
+
+    // Also when the readback fails halfway
+    dsi.clear();
+    assert_eq!(
+        panel.memory_check(&mut dsi.clone(), REGION, 64),
+        Err(Error::Dsi(MockError::NoReply))
+    );
+    let transactions = dsi.transactions();
+    assert_eq!(transactions[transactions.len() - 3..], restore[..]);
+}
+
+#[test]
+fn memory_check_checks_its_arguments() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    assert_eq!(
+        panel.memory_check(&mut dsi.clone(), Window::new(0, 0, 480, 0), 64),
+        Err(Error::OutOfBounds)
+    );
+    assert_eq!(
+        panel.memory_check(&mut dsi.clone(), REGION, 3),
+        Err(Error::WriteLength)
+    );
+    assert!(dsi.transactions().is_empty());
+
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.memory_check(&mut dsi.clone(), REGION, 64),
+        Err(Error::PowerState)
+    );
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use common::{config, init, long, short};
+use otm8009a::mock::{MockDsi, MockError};
+use otm8009a::{ColorMap, Error, FrameRate, Mode, Orientation, Otm8009A, PowerState, Rotation};
+
+#[test]
+fn set_orientation_rewrites_madctr_and_window() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Bgr));
+
+    // Upside down keeps the axes
+    panel
+        .set_orientation(&mut dsi.clone(), Orientation::new(Rotation::Deg180))
+        .unwrap();
+    // A quarter turn exchanges them
+    let mirrored = Orientation {
+        rotation: Rotation::Deg270,
+        flip_horizontal: true,
+        flip_vertical: false,
+    };
+    panel.set_orientation(&mut dsi.clone(), mirrored).unwrap();
+
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            short(0x36, 0xc8),
+            long(0x2a, &[0x00, 0x00, 0x01, 0xdf]),
+            long(0x2b, &[0x00, 0x00, 0x03, 0x1f]),
+            short(0x36, 0x28),
+            long(0x2a, &[0x00, 0x00, 0x03, 0x1f]),
+            long(0x2b, &[0x00, 0x00, 0x01, 0xdf]),
+        ]
+    );
+    let config = panel.config().unwrap();
+    assert_eq!(config.orientation, mirrored);
+    assert_eq!((config.cols, config.rows), (800, 480));
+}
+
+#[test]
+fn set_color_map_only_touches_madctr() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb));
+    panel
+        .set_color_map(&mut dsi.clone(), ColorMap::Bgr)
+        .unwrap();
+    panel
+        .set_color_map(&mut dsi.clone(), ColorMap::Rgb)
+        .unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![short(0x36, 0x68), short(0x36, 0x60)]
+    );
+    assert_eq!(panel.config().unwrap().color_map, ColorMap::Rgb);
+}
+
+#[test]
+fn failed_write_keeps_config() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb));
+    dsi.fail_at(0);
+    assert_eq!(
+        panel.set_color_map(&mut dsi.clone(), ColorMap::Bgr),
+        Err(Error::Dsi(MockError::Injected))
+    );
+    assert_eq!(panel.config().unwrap().color_map, ColorMap::Rgb);
+}
+
+#[test]
+fn config_follows_the_written_madctr() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    dsi.fail_at(0);
+    assert_eq!(
+        panel.set_orientation(&mut dsi.clone(), Mode::Landscape.into()),
+        Err(Error::Dsi(MockError::Injected))
+    );
+    assert_eq!(panel.config().unwrap().orientation, Mode::Portrait.into());
+
+    // MADCTR went through, the window did not
+    dsi.clear();
+    dsi.fail_at(1);
+    assert_eq!(
+        panel.set_orientation(&mut dsi.clone(), Mode::Landscape.into()),
+        Err(Error::Dsi(MockError::Injected))
+    );
+    assert_eq!(dsi.transactions(), vec![short(0x36, 0x60)]);
+    let config = panel.config().unwrap();
+    assert_eq!(config.orientation, Mode::Landscape.into());
+    assert_eq!((config.cols, config.rows), (800, 480));
+}
+
+#[test]
+fn runtime_changes_need_init() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.set_orientation(&mut dsi.clone(), Mode::Landscape.into()),
+        Err(Error::PowerState)
+    );
+    assert_eq!(
+        panel.set_color_map(&mut dsi.clone(), ColorMap::Bgr),
+        Err(Error::PowerState)
+    );
+    assert!(dsi.transactions().is_empty());
+}
+
+#[test]
+fn set_orientation_keeps_power_state() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    panel.display_off(&mut dsi.clone()).unwrap();
+    panel
+        .set_orientation(&mut dsi.clone(), Mode::Landscape.into())
+        .unwrap();
+    assert_eq!(panel.power_state(), PowerState::Awake);
+}
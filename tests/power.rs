@@ -0,0 +1,198 @@
+mod common;
+
+use common::{config, init, long, short};
+use otm8009a::mock::WriteCommand;
+use otm8009a::mock::{MockDsi, Transaction};
+use otm8009a::{
+    ColorMap, ConfigError, Error, FrameRate, Mode, Orientation, Otm8009A, Otm8009AConfig,
+    PowerState, Rotation,
+};
+
+#[test]
+fn sleep_wake_cycle() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    assert_eq!(panel.power_state(), PowerState::DisplayOn);
+
+    panel.display_off(&mut dsi.clone()).unwrap();
+    assert_eq!(panel.power_state(), PowerState::Awake);
+    panel.sleep(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    assert_eq!(panel.power_state(), PowerState::Sleeping);
+    panel.wake(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    assert_eq!(panel.power_state(), PowerState::Awake);
+    panel.display_on(&mut dsi.clone()).unwrap();
+    assert_eq!(panel.power_state(), PowerState::DisplayOn);
+
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            short(0x28, 0x00),
+            short(0x10, 0x00),
+            Transaction::Delay(5),
+            short(0x11, 0x00),
+            Transaction::Delay(120),
+            short(0x29, 0x00),
+        ]
+    );
+}
+
+#[test]
+fn wake_restores_display_on() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    panel.sleep(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    panel.wake(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    assert_eq!(panel.power_state(), PowerState::DisplayOn);
+}
+
+#[test]
+fn wrong_power_state_is_rejected() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    assert_eq!(panel.power_state(), PowerState::Uninitialized);
+    assert_eq!(
+        panel.sleep(&mut dsi.clone(), &mut dsi.delay()),
+        Err(Error::PowerState)
+    );
+    assert_eq!(panel.display_on(&mut dsi.clone()), Err(Error::PowerState));
+
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    assert_eq!(
+        panel.wake(&mut dsi.clone(), &mut dsi.delay()),
+        Err(Error::PowerState)
+    );
+    panel.sleep(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    assert_eq!(
+        panel.sleep(&mut dsi.clone(), &mut dsi.delay()),
+        Err(Error::PowerState)
+    );
+    assert_eq!(dsi.transactions().len(), 2);
+}
+
+#[test]
+fn idle_mode() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    panel.enter_idle_mode(&mut dsi.clone()).unwrap();
+    assert!(panel.is_idle());
+    panel.exit_idle_mode(&mut dsi.clone()).unwrap();
+    assert!(!panel.is_idle());
+    assert_eq!(
+        dsi.transactions(),
+        vec![short(0x39, 0x00), short(0x38, 0x00)]
+    );
+
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.enter_idle_mode(&mut dsi.clone()),
+        Err(Error::PowerState)
+    );
+}
+
+#[test]
+fn idle_frame_rate_goes_to_the_high_nibble() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    let config = Otm8009AConfig {
+        idle_frame_rate: Some(FrameRate::_35Hz),
+        ..config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb)
+    };
+    panel
+        .init(&mut dsi.clone(), config, &mut dsi.delay())
+        .unwrap();
+    let writes = dsi.writes();
+    let osc = writes
+        .iter()
+        .position(|w| {
+            *w == WriteCommand::DcsShortP1 {
+                arg: 0x00,
+                data: 0x81,
+            }
+        })
+        .unwrap();
+    assert_eq!(
+        writes[osc + 1],
+        WriteCommand::DcsShortP1 {
+            arg: 0xc1,
+            data: 0x05
+        }
+    );
+}
+
+#[test]
+fn partial_mode() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    panel.set_partial_area(&mut dsi.clone(), 0, 39).unwrap();
+    panel.enter_partial_mode(&mut dsi.clone()).unwrap();
+    assert!(panel.is_partial());
+    panel.enter_normal_mode(&mut dsi.clone()).unwrap();
+    assert!(!panel.is_partial());
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            long(0x30, &[0x00, 0x00, 0x00, 0x27]),
+            short(0x12, 0x00),
+            short(0x13, 0x00),
+        ]
+    );
+}
+
+#[test]
+fn partial_area_follows_orientation() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb));
+    assert_eq!(
+        panel.set_partial_area(&mut dsi.clone(), 400, 800),
+        Err(Error::OutOfBounds)
+    );
+    assert_eq!(
+        panel.set_partial_area(&mut dsi.clone(), 20, 10),
+        Err(Error::OutOfBounds)
+    );
+    assert!(dsi.transactions().is_empty());
+
+    // Upside down, the top strip is lit at the bottom of the glass
+    for orientation in [
+        Orientation::new(Rotation::Deg180),
+        Orientation {
+            flip_vertical: true,
+            ..Orientation::new(Rotation::Deg0)
+        },
+    ] {
+        panel
+            .set_orientation(&mut dsi.clone(), orientation)
+            .unwrap();
+        dsi.clear();
+        panel.set_partial_area(&mut dsi.clone(), 0, 39).unwrap();
+        assert_eq!(
+            dsi.transactions(),
+            vec![long(0x30, &[0x02, 0xf8, 0x03, 0x1f])]
+        );
+    }
+
+    // Rows of a landscape configuration run across the panel lines
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb));
+    assert_eq!(
+        panel.set_partial_area(&mut dsi.clone(), 0, 39),
+        Err(Error::InvalidConfig(ConfigError::OrientationMismatch))
+    );
+    assert_eq!(
+        panel.set_partial_area(&mut dsi.clone(), 0, 480),
+        Err(Error::OutOfBounds)
+    );
+    assert!(dsi.transactions().is_empty());
+}
+
+#[test]
+fn partial_lines_ignore_orientation() {
+    let (mut panel, dsi) = init(config(FrameRate::_60Hz, Mode::Landscape, ColorMap::Rgb));
+    panel.set_partial_lines(&mut dsi.clone(), 400, 799).unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![long(0x30, &[0x01, 0x90, 0x03, 0x1f])]
+    );
+    assert_eq!(
+        panel.set_partial_lines(&mut dsi.clone(), 400, 800),
+        Err(Error::OutOfBounds)
+    );
+    assert_eq!(
+        Otm8009A::new().set_partial_lines(&mut dsi.clone(), 0, 39),
+        Err(Error::PowerState)
+    );
+}
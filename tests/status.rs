@@ -0,0 +1,123 @@
+use otm8009a::mock::{MockDsi, ReadCommand, Transaction, WriteCommand};
+use otm8009a::{
+    AddressMode, DisplayStatus, DsiErrorCount, ImageMode, Otm8009A, PixelFormat, PowerMode,
+    SelfDiagnostic, SignalMode,
+};
+
+fn read(arg: u8, len: usize) -> Transaction {
+    Transaction::Read {
+        command: ReadCommand::DcsShort { arg },
+        len,
+    }
+}
+
+#[test]
+fn single_byte_registers_are_decoded() {
+    let dsi = MockDsi::new();
+    for reply in [0x9c, 0x68, 0x77, 0x00, 0xa1, 0x40, 0xc0, 0x83] {
+        dsi.push_reply(&[reply]);
+    }
+    let mut panel = Otm8009A::new();
+    let mut bus = dsi.clone();
+
+    assert_eq!(
+        panel.power_mode(&mut bus),
+        Ok(PowerMode {
+            booster_on: true,
+            idle_mode: false,
+            partial_mode: false,
+            sleep_out: true,
+            normal_mode: true,
+            display_on: true,
+        })
+    );
+    assert_eq!(
+        panel.address_mode(&mut bus),
+        Ok(AddressMode {
+            row_column_exchange: true,
+            column_order: true,
+            bgr: true,
+            ..AddressMode::default()
+        })
+    );
+    assert_eq!(
+        panel.read_pixel_format(&mut bus),
+        Ok(Some(PixelFormat::Rgb888))
+    );
+    assert_eq!(panel.read_pixel_format(&mut bus), Ok(None));
+    assert_eq!(
+        panel.image_mode(&mut bus),
+        Ok(ImageMode {
+            vertical_scrolling: true,
+            inversion: true,
+            gamma_curve: 1,
+            ..ImageMode::default()
+        })
+    );
+    assert_eq!(
+        panel.signal_mode(&mut bus),
+        Ok(SignalMode {
+            tearing_effect: false,
+            tearing_effect_hblank: true,
+        })
+    );
+    assert_eq!(
+        panel.self_diagnostic(&mut bus),
+        Ok(SelfDiagnostic {
+            register_loading: true,
+            functionality: true,
+            ..SelfDiagnostic::default()
+        })
+    );
+    assert_eq!(
+        panel.dsi_error_count(&mut bus),
+        Ok(DsiErrorCount {
+            count: 3,
+            overflow: true,
+        })
+    );
+
+    assert_eq!(
+        dsi.transactions(),
+        [0x0a, 0x0b, 0x0c, 0x0c, 0x0d, 0x0e, 0x0f, 0x05].map(|arg| read(arg, 1))
+    );
+}
+
+#[test]
+fn address_mode_round_trips() {
+    for bits in 0..=u8::MAX {
+        let bits = bits & 0xfc;
+        assert_eq!(AddressMode::from_bits(bits).bits(), bits);
+    }
+}
+
+#[test]
+fn display_status_is_decoded() {
+    let dsi = MockDsi::new();
+    // Booster on, MV | MX, 24 bit, sleep out, normal mode, display on, TE on,
+    // gamma curve 1 (GCS0), TE with H-blanking
+    dsi.push_reply(&[0xb0, 0x73, 0x06, 0x60]);
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.display_status(&mut dsi.clone()),
+        Ok(DisplayStatus {
+            booster_on: true,
+            address_mode: AddressMode::from_bits(0x60),
+            interface_pixel_format: 0b111,
+            sleep_out: true,
+            normal_mode: true,
+            display_on: true,
+            tearing_effect: true,
+            gamma_curve: 1,
+            tearing_effect_hblank: true,
+            ..DisplayStatus::default()
+        })
+    );
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            Transaction::Write(WriteCommand::SetMaximumReturnPacketSize(4)),
+            read(0x09, 4),
+        ]
+    );
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{long, short};
+use otm8009a::mock::{MockDsi, ReadCommand, Transaction, WriteCommand};
+use otm8009a::{Error, Otm8009A, TearingEffectMode};
+
+#[test]
+fn tearing_effect_modes_and_off() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    panel
+        .set_tearing_effect_scanline(&mut dsi.clone(), 799)
+        .unwrap();
+    panel
+        .enable_tearing_effect(&mut dsi.clone(), TearingEffectMode::VBlankHBlank)
+        .unwrap();
+    panel
+        .enable_tearing_effect(&mut dsi.clone(), TearingEffectMode::VBlank)
+        .unwrap();
+    panel.disable_tearing_effect(&mut dsi.clone()).unwrap();
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            long(0x44, &[0x03, 0x1f]),
+            short(0x35, 0x01),
+            short(0x35, 0x00),
+            short(0x34, 0x00),
+        ]
+    );
+}
+
+#[test]
+fn scanline_is_bounds_checked() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.set_tearing_effect_scanline(&mut dsi.clone(), 800),
+        Err(Error::OutOfBounds)
+    );
+    assert_eq!(
+        panel.enable_te_output(800, &mut dsi.clone()),
+        Err(Error::OutOfBounds)
+    );
+    assert!(dsi.transactions().is_empty());
+}
+
+#[test]
+fn scanline_readback() {
+    let dsi = MockDsi::new();
+    dsi.push_reply(&[0x01, 0x2c]);
+    let mut panel = Otm8009A::new();
+    assert_eq!(panel.scanline(&mut dsi.clone()), Ok(300));
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            Transaction::Write(WriteCommand::SetMaximumReturnPacketSize(2)),
+            Transaction::Read {
+                command: ReadCommand::DcsShort { arg: 0x45 },
+                len: 2,
+            },
+        ]
+    );
+}
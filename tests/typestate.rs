@@ -0,0 +1,84 @@
+mod common;
+
+use common::{config, short};
+use otm8009a::mock::{MockDsi, MockError, Transaction};
+use otm8009a::typestate::Panel;
+use otm8009a::{ColorMap, Error, FrameRate, Mode, PowerState};
+
+#[test]
+fn transitions_follow_the_driver_state() {
+    let dsi = MockDsi::new();
+    let panel = Panel::new()
+        .init(
+            &mut dsi.clone(),
+            config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb),
+            &mut dsi.delay(),
+        )
+        .unwrap();
+    assert_eq!(panel.driver().power_state(), PowerState::DisplayOn);
+    dsi.clear();
+
+    let panel = panel.display_off(&mut dsi.clone()).unwrap();
+    assert_eq!(panel.driver().power_state(), PowerState::Awake);
+    let panel = panel.sleep(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    assert_eq!(panel.driver().power_state(), PowerState::Sleeping);
+    let panel = panel.wake(&mut dsi.clone(), &mut dsi.delay()).unwrap();
+    let mut panel = panel.display_on(&mut dsi.clone()).unwrap();
+    panel.set_brightness(&mut dsi.clone(), 0x20).unwrap();
+
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            short(0x28, 0x00),
+            short(0x10, 0x00),
+            Transaction::Delay(5),
+            short(0x11, 0x00),
+            Transaction::Delay(120),
+            short(0x29, 0x00),
+            short(0x51, 0x20),
+        ]
+    );
+    assert_eq!(panel.into_inner().power_state(), PowerState::DisplayOn);
+}
+
+#[test]
+fn failed_transition_returns_the_panel() {
+    let dsi = MockDsi::new();
+    let config = config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb);
+    dsi.fail_at(0);
+    let Err((panel, error)) = Panel::new().init(&mut dsi.clone(), config, &mut dsi.delay()) else {
+        panic!("init did not fail");
+    };
+    assert_eq!(error, Error::Dsi(MockError::Injected));
+    assert_eq!(panel.driver().power_state(), PowerState::Uninitialized);
+
+    // The returned panel can retry in the same state
+    let panel = panel
+        .init(&mut dsi.clone(), config, &mut dsi.delay())
+        .unwrap();
+    dsi.clear();
+    dsi.fail_at(0);
+    let Err((panel, error)) = panel.display_off(&mut dsi.clone()) else {
+        panic!("display_off did not fail");
+    };
+    assert_eq!(error, Error::Dsi(MockError::Injected));
+    assert_eq!(panel.driver().power_state(), PowerState::DisplayOn);
+    panel.display_off(&mut dsi.clone()).unwrap();
+}
+
+#[test]
+fn id_is_checked_before_init() {
+    let dsi = MockDsi::new();
+    dsi.push_reply(&[0x40]);
+    let mut panel = Panel::new();
+    assert_eq!(panel.id_matches(&mut dsi.clone()), Ok(()));
+    assert_eq!(panel.config(), None);
+    let panel = panel
+        .init(
+            &mut dsi.clone(),
+            config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb),
+            &mut dsi.delay(),
+        )
+        .unwrap();
+    assert_eq!(panel.config().unwrap().cols, 480);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{lock, long, short, unlock};
+use otm8009a::mock::{MockDsi, MockError, ReadCommand, Transaction, WriteCommand};
+use otm8009a::{Error, Otm8009A};
+
+#[test]
+fn write_vendor_reg_unlocks_and_relocks_cmd2() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    panel
+        .write_vendor_reg(&mut dsi.clone(), 0xc480, &[0x30])
+        .unwrap();
+    panel
+        .write_vendor_reg(&mut dsi.clone(), 0xd800, &[0x79, 0x79])
+        .unwrap();
+    assert!(!panel.is_cmd2_unlocked());
+
+    let mut expected = unlock();
+    expected.extend([short(0x00, 0x80), short(0xc4, 0x30)]);
+    expected.extend(lock());
+    expected.extend(unlock());
+    expected.extend([short(0x00, 0x00), long(0xd8, &[0x79, 0x79])]);
+    expected.extend(lock());
+    assert_eq!(dsi.transactions(), expected);
+}
+
+#[test]
+fn write_vendor_reg_keeps_explicit_unlock() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    panel.unlock_cmd2(&mut dsi.clone()).unwrap();
+    assert!(panel.is_cmd2_unlocked());
+    dsi.clear();
+
+    panel
+        .write_vendor_reg(&mut dsi.clone(), 0xc590, &[0x34])
+        .unwrap();
+    panel
+        .write_vendor_reg(&mut dsi.clone(), 0xd900, &[0x4e])
+        .unwrap();
+    assert!(panel.is_cmd2_unlocked());
+    assert_eq!(
+        dsi.transactions(),
+        vec![
+            short(0x00, 0x90),
+            short(0xc5, 0x34),
+            short(0x00, 0x00),
+            short(0xd9, 0x4e),
+        ]
+    );
+
+    panel.lock_cmd2(&mut dsi.clone()).unwrap();
+    assert!(!panel.is_cmd2_unlocked());
+}
+
+#[test]
+fn failed_vendor_access_relocks_cmd2() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    dsi.fail_at(5);
+    assert_eq!(
+        panel.write_vendor_reg(&mut dsi.clone(), 0xc480, &[0x30]),
+        Err(Error::Dsi(MockError::Injected))
+    );
+    assert!(!panel.is_cmd2_unlocked());
+    let mut expected = unlock();
+    expected.push(short(0x00, 0x80));
+    expected.extend(lock());
+    assert_eq!(dsi.transactions(), expected);
+
+    // A missing read reply is returned ahead of the lock
+    dsi.clear();
+    assert_eq!(
+        panel.read_vendor_reg(&mut dsi.clone(), 0xd800, &mut [0; 2]),
+        Err(Error::Dsi(MockError::NoReply))
+    );
+    assert!(!panel.is_cmd2_unlocked());
+    assert_eq!(dsi.transactions()[7..], lock()[..]);
+}
+
+#[test]
+fn read_vendor_reg_sets_return_packet_size() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    dsi.push_reply(&[0x79, 0x78]);
+    let mut buf = [0; 2];
+    panel
+        .read_vendor_reg(&mut dsi.clone(), 0xd800, &mut buf)
+        .unwrap();
+    assert_eq!(buf, [0x79, 0x78]);
+
+    let mut expected = unlock();
+    expected.extend([
+        short(0x00, 0x00),
+        Transaction::Write(WriteCommand::SetMaximumReturnPacketSize(2)),
+        Transaction::Read {
+            command: ReadCommand::DcsShort { arg: 0xd8 },
+            len: 2,
+        },
+    ]);
+    expected.extend(lock());
+    assert_eq!(dsi.transactions(), expected);
+}
+
+#[test]
+fn read_vendor_reg_rejects_empty_buffer() {
+    let dsi = MockDsi::new();
+    let mut panel = Otm8009A::new();
+    assert_eq!(
+        panel.read_vendor_reg(&mut dsi.clone(), 0xd800, &mut []),
+        Err(Error::ReadLength)
+    );
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use common::{config, init, lock, long, short, unlock};
+use otm8009a::mock::MockDsi;
+use otm8009a::sequence::{ConfigParam, InitStep};
+use otm8009a::{
+    ColorMap, ConfigError, Error, FrameRate, Millivolts, Mode, Otm8009A, Otm8009AConfig,
+    DEFAULT_GVDD, DEFAULT_NGVDD, DEFAULT_VCOM, GVDD_RANGE, VCOM_RANGE,
+};
+
+fn portrait() -> Otm8009AConfig {
+    config(FrameRate::_60Hz, Mode::Portrait, ColorMap::Rgb)
+}
+
+#[test]
+fn millivolts_to_codes() {
+    assert_eq!(DEFAULT_VCOM.vcom_code(), Some(0x4e));
+    assert_eq!(Millivolts(-1000).vcom_code(), Some(0x39));
+    assert_eq!(VCOM_RANGE.0.vcom_code(), Some(0xff));
+    assert_eq!(VCOM_RANGE.1.vcom_code(), Some(0x00));
+    assert_eq!(Millivolts(-287).vcom_code(), None);
+    assert_eq!(Millivolts(-3476).vcom_code(), None);
+    assert_eq!(Millivolts(1000).vcom_code(), None);
+
+    assert_eq!(DEFAULT_GVDD.gvdd_code(), Some(0x79));
+    assert_eq!(DEFAULT_NGVDD.ngvdd_code(), Some(0x79));
+    assert_eq!(GVDD_RANGE.0.gvdd_code(), Some(0x00));
+    assert_eq!(Millivolts(4000).gvdd_code(), Some(56));
+    assert_eq!(Millivolts(3299).gvdd_code(), None);
+    assert_eq!(Millivolts(6001).gvdd_code(), None);
+    assert_eq!(Millivolts(4000).ngvdd_code(), None);
+}
+
+#[test]
+fn init_writes_configured_voltages() {
+    let dsi = MockDsi::new();
+    Otm8009A::new()
+        .init(
+            &mut dsi.clone(),
+            Otm8009AConfig {
+                vcom: Millivolts(-1000),
+                gvdd: Millivolts(5000),
+                ngvdd: Millivolts(-4500),
+                ..portrait()
+            },
+            &mut dsi.delay(),
+        )
+        .unwrap();
+    let transactions = dsi.transactions();
+    assert!(transactions.contains(&short(0xd9, 0x39)));
+    assert!(transactions.contains(&long(0xd8, &[136, 96])));
+}
+
+#[test]
+fn invalid_voltages_are_rejected() {
+    for (config, error) in [
+        (
+            Otm8009AConfig {
+                vcom: Millivolts(0),
+                ..portrait()
+            },
+            ConfigError::VcomRange,
+        ),
+        (
+            Otm8009AConfig {
+                gvdd: Millivolts(7000),
+                ..portrait()
+            },
+            ConfigError::GvddRange,
+        ),
+        (
+            Otm8009AConfig {
+                ngvdd: Millivolts(4800),
+                ..portrait()
+            },
+            ConfigError::GvddRange,
+        ),
+    ] {
+        assert_eq!(config.validate(), Err(error));
+    }
+
+    let (mut panel, dsi) = init(portrait());
+    assert_eq!(
+        panel.set_vcom(&mut dsi.clone(), Millivolts(-100)),
+        Err(Error::InvalidConfig(ConfigError::VcomRange))
+    );
+    assert_eq!(
+        panel.set_gvdd(&mut dsi.clone(), Millivolts(5000), Millivolts(-2000)),
+        Err(Error::InvalidConfig(ConfigError::GvddRange))
+    );
+    assert!(dsi.transactions().is_empty());
+    assert_eq!(panel.config(), Some(&portrait()));
+
+    assert_eq!(
+        Otm8009A::new().set_vcom(&mut dsi.clone(), DEFAULT_VCOM),
+        Err(Error::PowerState)
+    );
+}
+
+#[test]
+fn setters_write_vendor_registers() {
+    let (mut panel, dsi) = init(portrait());
+    panel.set_vcom(&mut dsi.clone(), Millivolts(-1100)).unwrap();
+    panel
+        .set_gvdd(&mut dsi.clone(), Millivolts(4500), Millivolts(-4600))
+        .unwrap();
+
+    let mut expected = unlock();
+    expected.extend([short(0x00, 0x00), short(0xd9, 0x41)]);
+    expected.extend(lock());
+    expected.extend(unlock());
+    expected.extend([short(0x00, 0x00), long(0xd8, &[96, 104])]);
+    expected.extend(lock());
+    assert_eq!(dsi.transactions(), expected);
+
+    let config = panel.config().unwrap();
+    assert_eq!(config.vcom, Millivolts(-1100));
+    assert_eq!(
+        (config.gvdd, config.ngvdd),
+        (Millivolts(4500), Millivolts(-4600))
+    );
+}
+
+#[test]
+fn config_long_is_bounded() {
+    const SEQUENCE: &[InitStep] = &[InitStep::ConfigLong(0xd8, &[ConfigParam::Gvdd; 17])];
+    let dsi = MockDsi::new();
+    assert_eq!(
+        Otm8009A::new().init_with_sequence(
+            &mut dsi.clone(),
+            portrait(),
+            SEQUENCE,
+            &mut dsi.delay()
+        ),
+        Err(Error::WriteLength)
+    );
+    assert!(dsi.transactions().is_empty());
+}